@@ -0,0 +1,62 @@
+use std::time::{Duration, Instant};
+use network::network::Matrix;
+
+// Run with `cargo bench --bench matrix_multiplication`
+fn main() {
+    for size in [256, 1024] {
+        let m1 = square(size, 3);
+        let m2 = square(size, 7);
+        let mut out = Matrix::zeros(size, size);
+        let iterations = if size > 512 { 3 } else { 20 };
+
+        let naive = time(iterations, || {
+            naive_multiplication(&m1, &m2);
+        });
+        let blocked = time(iterations, || {
+            Matrix::matrix_multiplication(&m1, &m2).unwrap();
+        });
+        let into = time(iterations, || {
+            Matrix::matrix_multiplication_into(&m1, &m2, &mut out).unwrap();
+        });
+
+        println!("{0}x{0}", size);
+        println!("  naive    {:>10.2?}", naive);
+        println!("  blocked  {:>10.2?}  {:.1}x", blocked, naive.as_secs_f64() / blocked.as_secs_f64());
+        println!("  into     {:>10.2?}  {:.1}x", into, naive.as_secs_f64() / into.as_secs_f64());
+    }
+}
+
+fn square(size: usize, seed: usize) -> Matrix {
+    return Matrix::from_fn(size, size, |col, row| ((((row * size + col) * seed) % 19) as f32) / 19.);
+}
+
+fn time<F: FnMut()>(iterations: u32, mut f: F) -> Duration {
+    f();
+
+    let start = Instant::now();
+
+    for _ in 0..iterations {
+        f();
+    }
+
+    return start.elapsed() / iterations;
+}
+
+// The triple loop the kernel replaced, kept as the baseline
+fn naive_multiplication(m1: &Matrix, m2: &Matrix) -> Matrix {
+    let mut result_elements = vec![];
+
+    for j in 0..m1.rows() {
+        for i in 0..m2.cols() {
+            let mut sum = 0.;
+
+            for k in 0..m1.cols() {
+                sum += m1.get(k, j) * m2.get(i, k);
+            }
+
+            result_elements.push(sum);
+        }
+    }
+
+    return Matrix::create(m2.cols(), m1.rows(), result_elements).unwrap();
+}
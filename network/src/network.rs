@@ -1,9 +1,33 @@
+mod conv2d;
+mod dense;
+mod dropout;
+mod flatten;
 mod layer;
+mod layer_norm;
 mod network;
 mod matrix;
+mod tensor;
 mod training_batch;
-mod activation;
+mod parallel;
+mod pooling;
+mod spatial;
+pub mod activation;
+pub mod initialiser;
+pub mod loss;
+pub mod optimizer;
+pub mod schedule;
+pub mod serialisation;
 
-pub use self::network::Network;
-pub use self::layer::Layer;
+pub use self::network::{Network, NetworkError};
+pub use self::conv2d::{Conv2D, Conv2DGradients, Padding};
+pub use self::pooling::{AvgPool2D, GlobalAvgPool, MaxPool2D};
+pub use self::dense::Dense;
+pub use self::dropout::Dropout;
+pub use self::flatten::Flatten;
+pub use self::layer::{Gradients, Layer, Saved};
+pub use self::layer_norm::LayerNorm;
+pub use self::matrix::{Axis, Bf16, Element, F16, Matrix, MatrixError, Norm};
+pub use self::tensor::{Tensor, TensorError};
 pub use self::training_batch::TrainingBatch;
+#[cfg(feature = "parallel")]
+pub use self::parallel::set_threads;
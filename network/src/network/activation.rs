@@ -1 +1,161 @@
-pub mod relu;
\ No newline at end of file
+use crate::network::loss::Loss;
+use crate::network::matrix::{Element, Matrix, MatrixError};
+
+pub use self::elu::Elu;
+pub use self::gelu::Gelu;
+pub use self::identity::Identity;
+pub use self::leaky_relu::LeakyRelu;
+pub use self::relu::Relu;
+pub use self::sigmoid::Sigmoid;
+pub use self::softmax::Softmax;
+pub use self::softplus::Softplus;
+pub use self::swish::Swish;
+pub use self::tanh::Tanh;
+
+mod elu;
+mod gelu;
+mod identity;
+mod leaky_relu;
+mod relu;
+mod sigmoid;
+mod softmax;
+mod softplus;
+mod swish;
+mod tanh;
+
+pub trait Activation<T: Element = f32>: Send + Sync {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T>;
+
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T>;
+
+    // Errors when e isn't the shape of m
+    fn backward(&self, m: &Matrix<T>, e: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        return Matrix::hadamard(&self.derivative(m), e);
+    }
+
+    fn output_delta(&self, m: &Matrix<T>, output: &Matrix<T>, expected: &Matrix<T>, loss: &dyn Loss<T>) -> Result<Matrix<T>, MatrixError> {
+        return self.backward(m, &loss.gradient(output, expected));
+    }
+
+    // Together with parameters this is enough for from_name to rebuild the activation
+    fn name(&self) -> &'static str;
+
+    fn parameters(&self) -> Vec<f32> {
+        return vec![];
+    }
+}
+
+pub fn from_name<T: Element>(name: &str, parameters: &[f32]) -> Option<Box<dyn Activation<T>>> {
+    return match (name, parameters) {
+        ("elu", [alpha]) => Some(Box::new(Elu::create(*alpha))),
+        ("gelu", []) => Some(Box::new(Gelu {})),
+        ("identity", []) => Some(Box::new(Identity {})),
+        ("leaky_relu", [alpha]) => Some(Box::new(LeakyRelu::create(*alpha))),
+        ("relu", []) => Some(Box::new(Relu {})),
+        ("sigmoid", []) => Some(Box::new(Sigmoid {})),
+        ("softmax", []) => Some(Box::new(Softmax {})),
+        ("softplus", []) => Some(Box::new(Softplus {})),
+        ("swish", []) => Some(Box::new(Swish {})),
+        ("tanh", []) => Some(Box::new(Tanh {})),
+        _ => None,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::activation::{Activation, Elu, Gelu, Identity, LeakyRelu, Relu, Sigmoid, Softmax, Softplus, Swish, Tanh};
+    use crate::network::loss::{CategoricalCrossEntropy, Loss, MeanSquaredError};
+    use crate::network::matrix::{Matrix, MatrixError};
+
+    fn assert_derivative_matches_slope(activation: &dyn Activation) {
+        let h = 0.001;
+        let m = Matrix::from_vec(vec![-2.3, -0.7, -0.1, 0.2, 0.9, 2.6]);
+        let above = activation.activate(&Matrix::map(&m, |v| v + h));
+        let below = activation.activate(&Matrix::map(&m, |v| v - h));
+        let derivative = activation.derivative(&m);
+
+        for i in 0..m.elements().len() {
+            let slope = (above.elements()[i] - below.elements()[i]) / (2. * h);
+
+            assert!((slope - derivative.elements()[i]).abs() < 0.01, "expected {} got {}", slope, derivative.elements()[i]);
+        }
+    }
+
+    #[test]
+    fn derivatives() {
+        assert_derivative_matches_slope(&Relu {});
+        assert_derivative_matches_slope(&Sigmoid {});
+        assert_derivative_matches_slope(&Tanh {});
+        assert_derivative_matches_slope(&LeakyRelu::create(0.01));
+        assert_derivative_matches_slope(&Elu::create(1.));
+        assert_derivative_matches_slope(&Gelu {});
+        assert_derivative_matches_slope(&Swish {});
+        assert_derivative_matches_slope(&Softplus {});
+        assert_derivative_matches_slope(&Identity {});
+    }
+
+    #[test]
+    fn sigmoid() {
+        let result: Matrix = Sigmoid {}.activate(&Matrix::from_vec(vec![0., 100., -100.]));
+
+        assert_eq!(result, Matrix::from_vec(vec![0.5, 1., 0.]));
+    }
+
+    #[test]
+    fn leaky_relu() {
+        let result: Matrix = LeakyRelu::create(0.1).activate(&Matrix::from_vec(vec![-2., 3.]));
+
+        assert_eq!(result, Matrix::from_vec(vec![-0.2, 3.]));
+    }
+
+    #[test]
+    fn softmax() {
+        let result: Matrix = Softmax {}.activate(&Matrix::create(3, 2, vec![1., 2., 3., 1000., 1000., 1000.]).unwrap());
+        let expected = Matrix::create(3, 2, vec![0.09003057, 0.24472848, 0.66524094, 1. / 3., 1. / 3., 1. / 3.]).unwrap();
+
+        for i in 0..expected.elements().len() {
+            assert!((result.elements()[i] - expected.elements()[i]).abs() < 0.000001);
+        }
+    }
+
+    #[test]
+    fn softmax_backward() {
+        let h = 0.001;
+        let softmax = Softmax {};
+        let m: Matrix = Matrix::from_vec(vec![0.3, -1.2, 0.8]);
+        let e = Matrix::from_vec(vec![0.5, -0.25, 1.]);
+        let result = softmax.backward(&m, &e).unwrap();
+
+        for i in 0..m.elements().len() {
+            let mut above = Matrix::from_vec(m.elements().to_vec());
+            let mut below = Matrix::from_vec(m.elements().to_vec());
+            above.elements_mut()[i] += h;
+            below.elements_mut()[i] -= h;
+            let slope = (Matrix::hadamard(&softmax.activate(&above), &e).unwrap().sum() - Matrix::hadamard(&softmax.activate(&below), &e).unwrap().sum()) / (2. * h);
+
+            assert!((slope - result.elements()[i]).abs() < 0.01, "expected {} got {}", slope, result.elements()[i]);
+        }
+    }
+
+    #[test]
+    fn softmax_output_delta() {
+        let softmax = Softmax {};
+        let m = Matrix::from_vec(vec![0.3, -1.2, 0.8]);
+        let output = softmax.activate(&m);
+        let t = Matrix::from_vec(vec![0., 0., 1.]);
+        let fused = softmax.output_delta(&m, &output, &t, &CategoricalCrossEntropy {}).unwrap();
+        let unfused = softmax.output_delta(&m, &output, &t, &MeanSquaredError {}).unwrap();
+
+        assert_eq!(fused, Matrix::from_vec(vec![output.elements()[0], output.elements()[1], output.elements()[2] - 1.]));
+        assert_eq!(unfused, softmax.backward(&m, &MeanSquaredError {}.gradient(&output, &t)).unwrap());
+    }
+
+    #[test]
+    fn backward_checks_shapes() {
+        let m: Matrix = Matrix::from_vec(vec![0.3, -1.2, 0.8]);
+        let e = Matrix::from_vec(vec![0.5, -0.25]);
+
+        assert_eq!(Tanh {}.backward(&m, &e), Err(MatrixError::ShapesDoNotMatch { operation: "hadamard", left: (3, 1), right: (2, 1) }));
+        assert_eq!(Softmax {}.backward(&m, &e), Err(MatrixError::ShapesDoNotMatch { operation: "softmax_backward", left: (3, 1), right: (2, 1) }));
+    }
+}
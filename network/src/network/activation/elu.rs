@@ -0,0 +1,48 @@
+use crate::network::activation::Activation;
+use crate::network::matrix::{Element, Matrix};
+
+pub struct Elu {
+    pub alpha: f32,
+}
+
+impl Elu {
+    pub fn create(alpha: f32) -> Elu {
+        return Elu {
+            alpha,
+        };
+    }
+}
+
+impl<T: Element> Activation<T> for Elu {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T> {
+        let alpha = T::from_f32(self.alpha);
+
+        return Matrix::map(m, |v| {
+            return if *v < T::zero() {
+                alpha * (v.exp() - T::one())
+            } else {
+                *v
+            }
+        });
+    }
+
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T> {
+        let alpha = T::from_f32(self.alpha);
+
+        return Matrix::map(m, |v| {
+            return if *v < T::zero() {
+                alpha * v.exp()
+            } else {
+                T::one()
+            }
+        });
+    }
+
+    fn name(&self) -> &'static str {
+        return "elu";
+    }
+
+    fn parameters(&self) -> Vec<f32> {
+        return vec![self.alpha];
+    }
+}
@@ -0,0 +1,43 @@
+use std::f32::consts::FRAC_2_PI;
+use crate::network::activation::Activation;
+use crate::network::matrix::{Element, Matrix};
+
+const COEFFICIENT: f32 = 0.044715;
+
+// Uses the tanh approximation
+// gelu(x) = 0.5x(1 + tanh(sqrt(2/pi)(x + 0.044715x^3)))
+pub struct Gelu {
+
+}
+
+impl<T: Element> Activation<T> for Gelu {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T> {
+        let scale = T::from_f32(FRAC_2_PI.sqrt());
+        let coefficient = T::from_f32(COEFFICIENT);
+        let half = T::from_f32(0.5);
+
+        return Matrix::map(m, |v| {
+            let t = (scale * (*v + coefficient * v.powi(3))).tanh();
+
+            return half * *v * (T::one() + t);
+        });
+    }
+
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T> {
+        let scale = T::from_f32(FRAC_2_PI.sqrt());
+        let coefficient = T::from_f32(COEFFICIENT);
+        let half = T::from_f32(0.5);
+        let three = T::from_f32(3.);
+
+        return Matrix::map(m, |v| {
+            let t = (scale * (*v + coefficient * v.powi(3))).tanh();
+            let dt = (T::one() - (t * t)) * scale * (T::one() + three * coefficient * *v * *v);
+
+            return half * (T::one() + t) + half * *v * dt;
+        });
+    }
+
+    fn name(&self) -> &'static str {
+        return "gelu";
+    }
+}
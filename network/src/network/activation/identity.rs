@@ -0,0 +1,20 @@
+use crate::network::activation::Activation;
+use crate::network::matrix::{Element, Matrix};
+
+pub struct Identity {
+
+}
+
+impl<T: Element> Activation<T> for Identity {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::map(m, |v| *v);
+    }
+
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::map(m, |_| T::one());
+    }
+
+    fn name(&self) -> &'static str {
+        return "identity";
+    }
+}
@@ -0,0 +1,48 @@
+use crate::network::activation::Activation;
+use crate::network::matrix::{Element, Matrix};
+
+pub struct LeakyRelu {
+    pub alpha: f32,
+}
+
+impl LeakyRelu {
+    pub fn create(alpha: f32) -> LeakyRelu {
+        return LeakyRelu {
+            alpha,
+        };
+    }
+}
+
+impl<T: Element> Activation<T> for LeakyRelu {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T> {
+        let alpha = T::from_f32(self.alpha);
+
+        return Matrix::map(m, |v| {
+            return if *v < T::zero() {
+                alpha * *v
+            } else {
+                *v
+            }
+        });
+    }
+
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T> {
+        let alpha = T::from_f32(self.alpha);
+
+        return Matrix::map(m, |v| {
+            return if *v < T::zero() {
+                alpha
+            } else {
+                T::one()
+            }
+        });
+    }
+
+    fn name(&self) -> &'static str {
+        return "leaky_relu";
+    }
+
+    fn parameters(&self) -> Vec<f32> {
+        return vec![self.alpha];
+    }
+}
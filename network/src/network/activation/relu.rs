@@ -1,27 +1,32 @@
-use crate::network::matrix::Matrix;
+use crate::network::activation::Activation;
+use crate::network::matrix::{Element, Matrix};
 
 pub struct Relu {
 
 }
 
-impl Relu {
-    pub fn activate(m: &Matrix) -> Matrix {
+impl<T: Element> Activation<T> for Relu {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T> {
         return Matrix::map(m, |v| {
-            return if *v < 0. {
-                0.
+            return if *v < T::zero() {
+                T::zero()
             } else {
                 *v
             }
         });
     }
 
-    pub fn derivative(m: &Matrix) -> Matrix {
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T> {
         return Matrix::map(m, |v| {
-            return if *v < 0. {
-                0.
+            return if *v < T::zero() {
+                T::zero()
             } else {
-                1.
+                T::one()
             }
         });
     }
-}
\ No newline at end of file
+
+    fn name(&self) -> &'static str {
+        return "relu";
+    }
+}
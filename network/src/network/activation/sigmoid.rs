@@ -0,0 +1,30 @@
+use crate::network::activation::Activation;
+use crate::network::matrix::{Element, Matrix};
+
+pub struct Sigmoid {
+
+}
+
+impl Sigmoid {
+    pub fn sigmoid<T: Element>(v: T) -> T {
+        return T::one() / (T::one() + (-v).exp());
+    }
+}
+
+impl<T: Element> Activation<T> for Sigmoid {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::map(m, |v| Sigmoid::sigmoid(*v));
+    }
+
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::map(m, |v| {
+            let s = Sigmoid::sigmoid(*v);
+
+            return s * (T::one() - s);
+        });
+    }
+
+    fn name(&self) -> &'static str {
+        return "sigmoid";
+    }
+}
@@ -0,0 +1,73 @@
+use crate::network::activation::Activation;
+use crate::network::loss::Loss;
+use crate::network::matrix::{Element, Matrix, MatrixError};
+
+// Normalises each row into a probability distribution so it only makes sense as the final layer.
+// Paired with cross-entropy the output delta collapses the softmax Jacobian to p - t
+pub struct Softmax {
+
+}
+
+impl<T: Element> Activation<T> for Softmax {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T> {
+        let mut result = m.clone();
+        let cols = m.cols().max(1);
+
+        for row in result.elements_mut().chunks_mut(cols) {
+            // Shifting by the max keeps exp from overflowing without changing the result
+            let max = row.iter().fold(T::from_f32(f32::NEG_INFINITY), |acc, e| acc.max(*e));
+
+            for e in row.iter_mut() {
+                *e = (*e - max).exp();
+            }
+
+            let total = row.iter().fold(T::zero(), |acc, e| acc + *e);
+
+            for e in row.iter_mut() {
+                *e /= total;
+            }
+        }
+
+        return result;
+    }
+
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T> {
+        // Only the diagonal of the Jacobian, backward applies the whole thing
+        return Matrix::map(&self.activate(m), |s| *s * (T::one() - *s));
+    }
+
+    fn backward(&self, m: &Matrix<T>, e: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        if (m.cols(), m.rows()) != (e.cols(), e.rows()) {
+            return Err(MatrixError::ShapesDoNotMatch {
+                operation: "softmax_backward",
+                left: (m.cols(), m.rows()),
+                right: (e.cols(), e.rows()),
+            });
+        }
+
+        // r = s * (e - sum(e * s)) for each row
+        let mut result = self.activate(m);
+        let cols = result.cols().max(1);
+
+        for (r_row, e_row) in result.elements_mut().chunks_mut(cols).zip(e.elements().chunks(cols)) {
+            let weighted = r_row.iter().zip(e_row).fold(T::zero(), |acc, (s, e)| acc + *s * *e);
+
+            for (r, e) in r_row.iter_mut().zip(e_row) {
+                *r *= *e - weighted;
+            }
+        }
+
+        return Ok(result);
+    }
+
+    fn output_delta(&self, m: &Matrix<T>, output: &Matrix<T>, expected: &Matrix<T>, loss: &dyn Loss<T>) -> Result<Matrix<T>, MatrixError> {
+        return match loss.softmax_gradient(output, expected) {
+            Some(r) => Ok(r),
+            None => self.backward(m, &loss.gradient(output, expected)),
+        };
+    }
+
+    fn name(&self) -> &'static str {
+        return "softmax";
+    }
+}
@@ -0,0 +1,22 @@
+use crate::network::activation::Activation;
+use crate::network::activation::Sigmoid;
+use crate::network::matrix::{Element, Matrix};
+
+pub struct Softplus {
+
+}
+
+impl<T: Element> Activation<T> for Softplus {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T> {
+        // ln(1 + e^x) rearranged so large inputs don't overflow
+        return Matrix::map(m, |v| v.max(T::zero()) + (-v.abs()).exp().ln_1p());
+    }
+
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::map(m, |v| Sigmoid::sigmoid(*v));
+    }
+
+    fn name(&self) -> &'static str {
+        return "softplus";
+    }
+}
@@ -0,0 +1,25 @@
+use crate::network::activation::Activation;
+use crate::network::activation::Sigmoid;
+use crate::network::matrix::{Element, Matrix};
+
+pub struct Swish {
+
+}
+
+impl<T: Element> Activation<T> for Swish {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::map(m, |v| *v * Sigmoid::sigmoid(*v));
+    }
+
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::map(m, |v| {
+            let s = Sigmoid::sigmoid(*v);
+
+            return s + *v * s * (T::one() - s);
+        });
+    }
+
+    fn name(&self) -> &'static str {
+        return "swish";
+    }
+}
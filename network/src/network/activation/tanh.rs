@@ -0,0 +1,24 @@
+use crate::network::activation::Activation;
+use crate::network::matrix::{Element, Matrix};
+
+pub struct Tanh {
+
+}
+
+impl<T: Element> Activation<T> for Tanh {
+    fn activate(&self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::map(m, |v| v.tanh());
+    }
+
+    fn derivative(&self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::map(m, |v| {
+            let t = v.tanh();
+
+            return T::one() - (t * t);
+        });
+    }
+
+    fn name(&self) -> &'static str {
+        return "tanh";
+    }
+}
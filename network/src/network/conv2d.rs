@@ -0,0 +1,521 @@
+use std::any::Any;
+use rand::Rng;
+use crate::network::activation::{self, Activation};
+use crate::network::initialiser::{Initialiser, XavierUniform, Zeros};
+use crate::network::layer::{self, Gradients, Layer, Saved};
+use crate::network::matrix::{Element, Matrix};
+use crate::network::spatial;
+use crate::network::tensor::{Tensor, TensorError};
+use crate::network::NetworkError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Padding {
+    // This many zeros around every side
+    Zeros(usize),
+    // Enough zeros that the output is the input divided by the stride, rounded up. When the
+    // padding can't be split evenly the extra zero goes on the bottom or right
+    Same,
+}
+
+// Slides filters over inputs of shape (batch, channels, height, width) and gives
+// (batch, filters, height, width). Like a dense layer the weights have a row for every input
+// a filter sees, here each (channel, kernel row, kernel col), and a column for every filter
+pub struct Conv2D<T: Element = f32> {
+    pub weights: Matrix<T>,
+    pub bias: Option<Matrix<T>>,
+    pub activation: Box<dyn Activation<T>>,
+    input: (usize, usize, usize),
+    kernel: (usize, usize),
+    stride: (usize, usize),
+    padding: Padding,
+    dilation: (usize, usize),
+}
+
+// Gradients of the loss with respect to the inputs and every parameter
+pub struct Conv2DGradients<T: Element = f32> {
+    pub inputs: Tensor<T>,
+    pub weights: Matrix<T>,
+    pub bias: Option<Matrix<T>>,
+}
+
+impl<T: Element> Conv2D<T> {
+    // input is (channels, height, width) and kernel is (height, width)
+    pub fn create(input: (usize, usize, usize), filters: usize, kernel: (usize, usize), activation: Box<dyn Activation<T>>, rng: &mut dyn Rng) -> Conv2D<T> {
+        return Conv2D::create_with_initialisers(input, filters, kernel, activation, &XavierUniform {}, &Zeros {}, rng);
+    }
+
+    pub fn create_with_initialisers(input: (usize, usize, usize), filters: usize, kernel: (usize, usize), activation: Box<dyn Activation<T>>, weights: &dyn Initialiser, bias: &dyn Initialiser, rng: &mut dyn Rng) -> Conv2D<T> {
+        let weights = weights.initialise(input.0 * kernel.0 * kernel.1, filters, rng).convert::<T>();
+        let bias = bias.initialise(1, filters, rng).convert::<T>();
+
+        return Conv2D::from_weights(input, kernel, activation, weights, Some(bias));
+    }
+
+    // Around weights that already exist, like ones read from a file. They need a row for every
+    // (channel, kernel row, kernel col) and the bias a column for every filter
+    pub fn from_weights(input: (usize, usize, usize), kernel: (usize, usize), activation: Box<dyn Activation<T>>, weights: Matrix<T>, bias: Option<Matrix<T>>) -> Conv2D<T> {
+        assert!(kernel.0 > 0 && kernel.1 > 0, "Conv2D kernel {:?} needs to be at least 1 on each side", kernel);
+        assert!(weights.rows() == input.0 * kernel.0 * kernel.1, "Conv2D weights need {} rows but have {}", input.0 * kernel.0 * kernel.1, weights.rows());
+        assert!(bias.as_ref().is_none_or(|b| b.cols() == weights.cols() && b.rows() == 1), "Conv2D bias needs a column for each of the {} filters", weights.cols());
+
+        return Conv2D {
+            weights,
+            bias,
+            activation,
+            input,
+            kernel,
+            stride: (1, 1),
+            padding: Padding::Zeros(0),
+            dilation: (1, 1),
+        };
+    }
+
+    pub fn with_stride(mut self, stride: (usize, usize)) -> Conv2D<T> {
+        assert!(stride.0 > 0 && stride.1 > 0, "Conv2D stride {:?} needs to be at least 1 on each side", stride);
+        self.stride = stride;
+
+        return self;
+    }
+
+    pub fn with_padding(mut self, padding: Padding) -> Conv2D<T> {
+        self.padding = padding;
+
+        return self;
+    }
+
+    // Spreads the kernel out so it covers every dilation-th position
+    pub fn with_dilation(mut self, dilation: (usize, usize)) -> Conv2D<T> {
+        assert!(dilation.0 > 0 && dilation.1 > 0, "Conv2D dilation {:?} needs to be at least 1 on each side", dilation);
+        self.dilation = dilation;
+
+        return self;
+    }
+
+    pub fn without_bias(mut self) -> Conv2D<T> {
+        self.bias = None;
+
+        return self;
+    }
+
+    // The activation is rebuilt by name, so this is None for activations from_name doesn't know
+    pub fn convert<U: Element>(&self) -> Option<Conv2D<U>> {
+        return Some(Conv2D {
+            weights: self.weights.convert::<U>(),
+            bias: self.bias.as_ref().map(|b| b.convert::<U>()),
+            activation: activation::from_name(self.activation.name(), &self.activation.parameters())?,
+            input: self.input,
+            kernel: self.kernel,
+            stride: self.stride,
+            padding: self.padding,
+            dilation: self.dilation,
+        });
+    }
+
+    pub fn filters(&self) -> usize {
+        return self.weights.cols();
+    }
+
+    // (channels, height, width) of a single input
+    pub fn input_shape(&self) -> (usize, usize, usize) {
+        return self.input;
+    }
+
+    // (height, width)
+    pub fn kernel(&self) -> (usize, usize) {
+        return self.kernel;
+    }
+
+    pub fn stride(&self) -> (usize, usize) {
+        return self.stride;
+    }
+
+    pub fn padding(&self) -> Padding {
+        return self.padding;
+    }
+
+    pub fn dilation(&self) -> (usize, usize) {
+        return self.dilation;
+    }
+
+    // (filters, height, width) of a single output
+    pub fn output_shape(&self) -> (usize, usize, usize) {
+        let (height, _) = self.extent(self.input.1, self.kernel.0, self.stride.0, self.dilation.0);
+        let (width, _) = self.extent(self.input.2, self.kernel.1, self.stride.1, self.dilation.1);
+
+        return (self.filters(), height, width);
+    }
+
+    // How many outputs fit along one side, and the zeros added before the first input
+    fn extent(&self, size: usize, kernel: usize, stride: usize, dilation: usize) -> (usize, usize) {
+        let span = dilation * (kernel - 1) + 1;
+
+        return match self.padding {
+            Padding::Zeros(padding) => match (size + 2 * padding).checked_sub(span) {
+                Some(room) => (room / stride + 1, padding),
+                None => (0, padding),
+            },
+            Padding::Same => {
+                let outputs = size.div_ceil(stride);
+                let padding = ((outputs.max(1) - 1) * stride + span).saturating_sub(size);
+
+                (outputs, padding / 2)
+            },
+        };
+    }
+
+    pub fn feed_forward(&self, inputs: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        return self.activate(&self.weighted_sum(inputs)?);
+    }
+
+    // Lowers the convolution to one matrix multiplication. Every output position gets a row of
+    // the inputs its kernel covers, in the same order as the weight rows, so cols * weights has a
+    // row per position and a column per filter
+    pub fn weighted_sum(&self, inputs: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        let batch = spatial::check_inputs("conv2d", inputs, self.input)?;
+        let mut y = Matrix::matrix_multiplication(&self.im2col(inputs)?, &self.weights)?;
+
+        if let Some(bias) = self.bias.as_ref() {
+            y.add_in_place(bias)?;
+        }
+
+        let (filters, height, width) = self.output_shape();
+
+        return Ok(Tensor::create(vec![batch, height, width, filters], y.into_elements())?.permute(&[0, 3, 1, 2])?.contiguous());
+    }
+
+    pub fn activate(&self, weighted_sum: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        let activated = self.activation.activate(&Conv2D::rows(weighted_sum)?);
+
+        return Tensor::create(weighted_sum.shape().to_vec(), activated.into_elements());
+    }
+
+    // error is the gradient of the loss with respect to the activated output. Runs the
+    // multiplication in weighted_sum backwards, with col2im adding each patch's error back onto
+    // the inputs it was copied from
+    pub fn backward(&self, inputs: &Tensor<T>, weighted_sum: &Tensor<T>, error: &Tensor<T>) -> Result<Conv2DGradients<T>, TensorError> {
+        let batch = spatial::check_inputs("conv2d", inputs, self.input)?;
+        spatial::check_error("conv2d", weighted_sum, batch, self.output_shape())?;
+        spatial::check_error("conv2d", error, batch, self.output_shape())?;
+        let delta = self.activation.backward(&Conv2D::rows(weighted_sum)?, &Conv2D::rows(error)?)?;
+        let delta = Tensor::create(weighted_sum.shape().to_vec(), delta.into_elements())?.permute(&[0, 2, 3, 1])?;
+        let r = Matrix::create(self.filters(), delta.len() / self.filters().max(1), delta.to_vec())?;
+        let cols_error = Matrix::matrix_multiplication(&r, &Matrix::transposition(&self.weights))?;
+
+        return Ok(Conv2DGradients {
+            inputs: Tensor::create(inputs.shape().to_vec(), self.col2im(&cols_error, batch))?,
+            weights: Matrix::matrix_multiplication(&Matrix::transposition(&self.im2col(inputs)?), &r)?,
+            bias: self.bias.as_ref().map(|_| r.sum_rows()),
+        });
+    }
+
+    // The bytes of the im2col matrix for a batch of this size, which backward builds again
+    // alongside its own matrix of the same size for the error
+    pub fn im2col_bytes(&self, batch: usize) -> usize {
+        let (_, height, width) = self.output_shape();
+
+        return batch * height * width * self.weights.rows() * std::mem::size_of::<T>();
+    }
+
+    fn im2col(&self, inputs: &Tensor<T>) -> Result<Matrix<T>, TensorError> {
+        let x = inputs.to_vec();
+        let patch = self.weights.rows();
+        let mut elements = vec![T::zero(); self.im2col_bytes(inputs.shape()[0]) / std::mem::size_of::<T>()];
+
+        self.lower(inputs.shape()[0], |col, input| {
+            elements[col] = x[input];
+        });
+
+        return Ok(Matrix::create(patch, elements.len() / patch.max(1), elements)?);
+    }
+
+    // Adds every element of the im2col shaped matrix onto the input it came from
+    fn col2im(&self, cols: &Matrix<T>, batch: usize) -> Vec<T> {
+        let (channels, height, width) = self.input;
+        let mut inputs = vec![T::zero(); batch * channels * height * width];
+
+        self.lower(batch, |col, input| {
+            inputs[input] += cols.elements()[col];
+        });
+
+        return inputs;
+    }
+
+    // Calls visit with where each input lands in the im2col matrix and where it is in the inputs.
+    // The padding is all zeros so it is skipped, leaving those elements of the matrix at zero
+    fn lower<F: FnMut(usize, usize)>(&self, batch: usize, mut visit: F) -> () {
+        let (channels, height, width) = self.input;
+        let (_, out_height, out_width) = self.output_shape();
+        let (kernel_height, kernel_width) = self.kernel;
+        let (_, top) = self.extent(height, kernel_height, self.stride.0, self.dilation.0);
+        let (_, left) = self.extent(width, kernel_width, self.stride.1, self.dilation.1);
+        let patch = self.weights.rows();
+
+        for n in 0..batch {
+            for oh in 0..out_height {
+                for ow in 0..out_width {
+                    let row = ((n * out_height + oh) * out_width + ow) * patch;
+
+                    for c in 0..channels {
+                        for i in 0..kernel_height {
+                            let ih = match (oh * self.stride.0 + i * self.dilation.0).checked_sub(top) {
+                                Some(ih) if ih < height => ih,
+                                _ => continue,
+                            };
+
+                            for j in 0..kernel_width {
+                                let iw = match (ow * self.stride.1 + j * self.dilation.1).checked_sub(left) {
+                                    Some(iw) if iw < width => iw,
+                                    _ => continue,
+                                };
+
+                                visit(row + (c * kernel_height + i) * kernel_width + j, ((n * channels + c) * height + ih) * width + iw);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Activations work on matrices with a row per sample
+    fn rows(t: &Tensor<T>) -> Result<Matrix<T>, TensorError> {
+        let batch = t.shape()[0];
+
+        return Ok(Matrix::create(t.len() / batch.max(1), batch, t.to_vec())?);
+    }
+}
+
+impl<T: Element> Layer<T> for Conv2D<T> {
+    fn output_shape(&self, _input: &[usize]) -> Vec<usize> {
+        let (filters, height, width) = Conv2D::output_shape(self);
+
+        return vec![filters, height, width];
+    }
+
+    fn forward(&self, inputs: &Tensor<T>, _rng: Option<&mut dyn Rng>) -> Result<(Tensor<T>, Saved<T>), NetworkError> {
+        let (channels, height, width) = self.input;
+        let y = self.weighted_sum(&layer::samples(inputs, &[channels, height, width])?)?;
+
+        return Ok((self.activate(&y)?, Saved::tensors(vec![y])));
+    }
+
+    fn backward(&self, inputs: &Tensor<T>, saved: &Saved<T>, error: &Tensor<T>) -> Result<Gradients<T>, NetworkError> {
+        let (channels, height, width) = self.input;
+        let gradients = Conv2D::backward(self, &layer::samples(inputs, &[channels, height, width])?, &saved.tensors[0], error)?;
+
+        return Ok(Gradients {
+            inputs: gradients.inputs.reshape(inputs.shape().to_vec())?,
+            parameters: vec![Some(gradients.weights), gradients.bias].into_iter().flatten().collect::<Vec<Matrix<T>>>(),
+        });
+    }
+
+    fn parameters(&self) -> Vec<&Matrix<T>> {
+        return vec![Some(&self.weights), self.bias.as_ref()].into_iter().flatten().collect::<Vec<&Matrix<T>>>();
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        return vec![Some(&mut self.weights), self.bias.as_mut()].into_iter().flatten().collect::<Vec<&mut Matrix<T>>>();
+    }
+
+    fn im2col_bytes(&self, batch: usize) -> usize {
+        return Conv2D::im2col_bytes(self, batch);
+    }
+
+    fn name(&self) -> &'static str {
+        return "conv2d";
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{ChaChaRng, SeedableRng};
+    use crate::network::activation::{Identity, Tanh};
+    use crate::network::conv2d::{Conv2D, Padding};
+    use crate::network::initialiser::Uniform;
+    use crate::network::tensor::{Tensor, TensorError};
+
+    fn input(shape: Vec<usize>, seed: u32) -> Tensor<f64> {
+        let len = shape.iter().product::<usize>();
+        let elements = (0..len).map(|v| (((v as u32 * 7 + seed * 13) % 17) as f64 - 8.) / 8.).collect::<Vec<f64>>();
+
+        return Tensor::create(shape, elements).unwrap();
+    }
+
+    fn conv(input: (usize, usize, usize), filters: usize, kernel: (usize, usize), seed: u32) -> Conv2D<f64> {
+        return Conv2D::create_with_initialisers(input, filters, kernel, Box::new(Identity {}), &Uniform::create(-1., 1.), &Uniform::create(-1., 1.), &mut ChaChaRng::from_seed(&[seed]));
+    }
+
+    // Pads the whole input first then lines the dilated kernel up with every stride-th position
+    fn naive(conv: &Conv2D<f64>, x: &Tensor<f64>, top: usize, left: usize) -> Tensor<f64> {
+        let (channels, height, width) = conv.input;
+        let (filters, out_height, out_width) = conv.output_shape();
+        let (kernel_height, kernel_width) = conv.kernel;
+        let batch = x.shape()[0];
+        let padded_height = (out_height - 1) * conv.stride.0 + conv.dilation.0 * (kernel_height - 1) + 1;
+        let padded_width = (out_width - 1) * conv.stride.1 + conv.dilation.1 * (kernel_width - 1) + 1;
+        let mut padded = Tensor::zeros(vec![batch, channels, padded_height.max(height + top), padded_width.max(width + left)]);
+
+        for n in 0..batch {
+            for c in 0..channels {
+                for h in 0..height {
+                    for w in 0..width {
+                        padded.set(&[n, c, h + top, w + left], x.get(&[n, c, h, w]));
+                    }
+                }
+            }
+        }
+
+        let mut out = Tensor::zeros(vec![batch, filters, out_height, out_width]);
+
+        for n in 0..batch {
+            for f in 0..filters {
+                for oh in 0..out_height {
+                    for ow in 0..out_width {
+                        let mut sum = conv.bias.as_ref().map_or(0., |b| b.get(f, 0));
+
+                        for c in 0..channels {
+                            for i in 0..kernel_height {
+                                for j in 0..kernel_width {
+                                    let weight = conv.weights.get(f, (c * kernel_height + i) * kernel_width + j);
+
+                                    sum += weight * padded.get(&[n, c, oh * conv.stride.0 + i * conv.dilation.0, ow * conv.stride.1 + j * conv.dilation.1]);
+                                }
+                            }
+                        }
+
+                        out.set(&[n, f, oh, ow], sum);
+                    }
+                }
+            }
+        }
+
+        return out;
+    }
+
+    fn assert_close(a: &Tensor<f64>, b: &Tensor<f64>) {
+        assert_eq!(a.shape(), b.shape());
+
+        for (x, y) in a.to_vec().iter().zip(b.to_vec().iter()) {
+            assert!((x - y).abs() < 1e-9, "{} != {}", x, y);
+        }
+    }
+
+    #[test]
+    fn forward_matches_naive() {
+        let x = input(vec![2, 3, 7, 6], 1);
+
+        assert_close(&conv((3, 7, 6), 4, (3, 3), 1).feed_forward(&x).unwrap(), &naive(&conv((3, 7, 6), 4, (3, 3), 1), &x, 0, 0));
+
+        let strided = conv((3, 7, 6), 2, (3, 2), 2).with_stride((2, 3)).with_padding(Padding::Zeros(1));
+        assert_eq!(strided.output_shape(), (2, 4, 3));
+        assert_close(&strided.feed_forward(&x).unwrap(), &naive(&strided, &x, 1, 1));
+
+        let dilated = conv((3, 7, 6), 3, (2, 3), 3).with_dilation((3, 2)).without_bias();
+        assert_eq!(dilated.output_shape(), (3, 4, 2));
+        assert_close(&dilated.feed_forward(&x).unwrap(), &naive(&dilated, &x, 0, 0));
+
+        // 7 rows need 1 row of padding above and below, 6 columns need 2 with one on the left
+        let same = conv((3, 7, 6), 2, (3, 4), 4).with_padding(Padding::Same);
+        assert_eq!(same.output_shape(), (2, 7, 6));
+        assert_close(&same.feed_forward(&x).unwrap(), &naive(&same, &x, 1, 1));
+
+        let same_strided = conv((3, 7, 6), 2, (3, 3), 5).with_padding(Padding::Same).with_stride((2, 2)).with_dilation((2, 1));
+        assert_eq!(same_strided.output_shape(), (2, 4, 3));
+        assert_close(&same_strided.feed_forward(&x).unwrap(), &naive(&same_strided, &x, 2, 0));
+    }
+
+    #[test]
+    fn backward_matches_slopes() {
+        let create = || conv((2, 5, 5), 3, (3, 2), 6).with_stride((2, 1)).with_padding(Padding::Same).with_dilation((1, 2));
+        let layer = Conv2D { activation: Box::new(Tanh {}), ..create() };
+        let x = input(vec![2, 2, 5, 5], 2);
+        let target = input(vec![2, 3, 3, 5], 3);
+        let loss = |layer: &Conv2D<f64>, x: &Tensor<f64>| {
+            let out = layer.feed_forward(x).unwrap().to_vec();
+
+            return out.iter().zip(target.to_vec()).map(|(o, t)| (o - t) * (o - t)).sum::<f64>() / 2.;
+        };
+        let y = layer.weighted_sum(&x).unwrap();
+        let out = layer.activate(&y).unwrap();
+        let error = Tensor::create(out.shape().to_vec(), out.to_vec().iter().zip(target.to_vec()).map(|(o, t)| o - t).collect::<Vec<f64>>()).unwrap();
+        let gradients = layer.backward(&x, &y, &error).unwrap();
+        let h = 1e-6;
+
+        for i in 0..layer.weights.elements().len() {
+            let mut above = Conv2D { activation: Box::new(Tanh {}), ..create() };
+            let mut below = Conv2D { activation: Box::new(Tanh {}), ..create() };
+            above.weights.elements_mut()[i] += h;
+            below.weights.elements_mut()[i] -= h;
+
+            assert!(((loss(&above, &x) - loss(&below, &x)) / (2. * h) - gradients.weights.elements()[i]).abs() < 1e-6);
+        }
+
+        for f in 0..3 {
+            let mut above = Conv2D { activation: Box::new(Tanh {}), ..create() };
+            above.bias.as_mut().unwrap().elements_mut()[f] += h;
+
+            assert!(((loss(&above, &x) - loss(&layer, &x)) / h - gradients.bias.as_ref().unwrap().get(f, 0)).abs() < 1e-4);
+        }
+
+        for (i, index) in [[0, 0, 0, 0], [1, 1, 2, 3], [0, 1, 4, 4], [1, 0, 3, 1]].iter().enumerate() {
+            let mut above = x.clone();
+            let mut below = x.clone();
+            above.set(index, x.get(index) + h);
+            below.set(index, x.get(index) - h);
+
+            assert!(((loss(&layer, &above) - loss(&layer, &below)) / (2. * h) - gradients.inputs.get(index)).abs() < 1e-6, "input {}", i);
+        }
+    }
+
+    #[test]
+    fn im2col() {
+        let layer = conv((2, 3, 3), 1, (2, 2), 1).with_padding(Padding::Zeros(1)).with_stride((2, 2));
+        let x = Tensor::create(vec![1, 2, 3, 3], (0..18).map(|v| v as f64 + 1.).collect::<Vec<f64>>()).unwrap();
+        let cols = layer.im2col(&x).unwrap();
+
+        // A row for each of the 2x2 output positions, each holding both channels' 2x2 patch
+        assert_eq!((cols.cols(), cols.rows()), (8, 4));
+        assert_eq!(cols.to_rows()[0], vec![0., 0., 0., 1., 0., 0., 0., 10.]);
+        assert_eq!(cols.to_rows()[3], vec![5., 6., 8., 9., 14., 15., 17., 18.]);
+        assert_eq!(layer.im2col_bytes(1), 8 * 4 * 8);
+        assert_eq!(conv((2, 3, 3), 1, (2, 2), 1).with_padding(Padding::Zeros(1)).with_stride((2, 2)).convert::<f32>().unwrap().im2col_bytes(10), 8 * 4 * 10 * 4);
+
+        // Every input appears once in the patches so putting them back gives the input
+        assert_eq!(layer.col2im(&cols, 1), x.to_vec());
+    }
+
+    #[test]
+    #[should_panic(expected = "Conv2D stride (0, 1) needs to be at least 1 on each side")]
+    fn zero_stride_panics() {
+        conv((1, 3, 3), 1, (2, 2), 1).with_stride((0, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Conv2D kernel (2, 0) needs to be at least 1 on each side")]
+    fn zero_kernel_panics() {
+        conv((1, 3, 3), 1, (2, 0), 1);
+    }
+
+    #[test]
+    fn wrong_inputs_error() {
+        let layer = conv((3, 4, 4), 2, (3, 3), 1);
+
+        assert_eq!(layer.feed_forward(&input(vec![3, 4, 4], 1)).err(), Some(TensorError::WrongRank { operation: "conv2d", expected: 4, shape: vec![3, 4, 4] }));
+        assert_eq!(layer.feed_forward(&input(vec![2, 2, 4, 4], 1)).err(), Some(TensorError::ShapesDoNotMatch { operation: "conv2d", expected: vec![2, 3, 4, 4], shape: vec![2, 2, 4, 4] }));
+    }
+
+    #[test]
+    fn error_must_match_outputs() {
+        let layer = conv((3, 4, 4), 2, (3, 3), 1);
+        let x = input(vec![1, 3, 4, 4], 1);
+        let y = layer.weighted_sum(&x).unwrap();
+
+        // The right number of values in the wrong shape, then too few
+        assert_eq!(layer.backward(&x, &y, &input(vec![1, 8], 2)).err(), Some(TensorError::ShapesDoNotMatch { operation: "conv2d", expected: vec![1, 2, 2, 2], shape: vec![1, 8] }));
+        assert_eq!(layer.backward(&x, &y, &input(vec![1, 2, 2, 1], 2)).err(), Some(TensorError::ShapesDoNotMatch { operation: "conv2d", expected: vec![1, 2, 2, 2], shape: vec![1, 2, 2, 1] }));
+    }
+}
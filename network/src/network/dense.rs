@@ -0,0 +1,121 @@
+use std::any::Any;
+use rand::Rng;
+use crate::network::activation::{self, Activation};
+use crate::network::initialiser::{Initialiser, XavierUniform, Zeros};
+use crate::network::layer::{Gradients, Layer, Saved};
+use crate::network::loss::Loss;
+use crate::network::matrix::{Element, Matrix, MatrixError};
+use crate::network::tensor::Tensor;
+use crate::network::NetworkError;
+
+// A fully connected layer. The weights have a row for each input and a last row for the bias,
+// with a column for each node
+pub struct Dense<T: Element = f32> {
+    pub weights: Matrix<T>,
+    pub activation: Box<dyn Activation<T>>,
+}
+
+impl<T: Element> Dense<T> {
+    pub fn create(num_of_nodes: usize, num_of_inputs: usize, activation: Box<dyn Activation<T>>, rng: &mut dyn Rng) -> Dense<T> {
+        return Dense::create_with_initialisers(num_of_nodes, num_of_inputs, activation, &XavierUniform {}, &Zeros {}, rng);
+    }
+
+    pub fn create_with_initialisers(num_of_nodes: usize, num_of_inputs: usize, activation: Box<dyn Activation<T>>, weights: &dyn Initialiser, bias: &dyn Initialiser, rng: &mut dyn Rng) -> Dense<T> {
+        let weights = weights.initialise(num_of_inputs, num_of_nodes, rng).convert::<T>();
+        let bias = bias.initialise(1, num_of_nodes, rng).convert::<T>();
+
+        return Dense {
+            weights: Matrix::extend_columns(&weights, bias.into_elements()).unwrap(),
+            activation,
+        };
+    }
+
+    // The activation is rebuilt by name, so this is None for activations from_name doesn't know
+    pub fn convert<U: Element>(&self) -> Option<Dense<U>> {
+        return Some(Dense {
+            weights: self.weights.convert::<U>(),
+            activation: activation::from_name(self.activation.name(), &self.activation.parameters())?,
+        });
+    }
+
+    pub fn feed_forward(&self, inputs: Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        return Ok(self.activate(&self.weighted_sum(&Dense::with_bias(&inputs)?)?));
+    }
+
+    pub fn with_bias(inputs: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        return Matrix::extend_rows(inputs, vec![T::one(); inputs.rows()]);
+    }
+
+    pub fn weighted_sum(&self, inputs_with_bias: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        return Matrix::matrix_multiplication(inputs_with_bias, &self.weights);
+    }
+
+    pub fn activate(&self, weighted_sum: &Matrix<T>) -> Matrix<T> {
+        return self.activation.activate(weighted_sum);
+    }
+
+    pub fn backward(&self, weighted_sum: &Matrix<T>, error: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        return self.activation.backward(weighted_sum, error);
+    }
+
+    pub fn weights_without_bias(&self) -> Matrix<T> {
+        return Matrix::from_fn(self.weights.cols(), self.weights.rows() - 1, |col, row| self.weights.get(col, row));
+    }
+
+    pub fn adjust_weights(&mut self, adjustment: &Matrix<T>) -> () {
+        self.weights += adjustment;
+    }
+
+    // The gradients once the error is through the activation
+    // g = transpose(x) * r
+    // e = r * transpose(m without bias)
+    fn gradients(&self, inputs: &Tensor<T>, r: &Matrix<T>) -> Result<Gradients<T>, NetworkError> {
+        let x = Dense::with_bias(&inputs.batch_matrix()?)?;
+        let e = Matrix::matrix_multiplication(r, &Matrix::transposition(&self.weights_without_bias()))?;
+
+        return Ok(Gradients {
+            inputs: Tensor::create(inputs.shape().to_vec(), e.into_elements())?,
+            parameters: vec![Matrix::matrix_multiplication(&Matrix::transposition(&x), r)?],
+        });
+    }
+}
+
+impl<T: Element> Layer<T> for Dense<T> {
+    fn output_shape(&self, _input: &[usize]) -> Vec<usize> {
+        return vec![self.weights.cols()];
+    }
+
+    fn forward(&self, inputs: &Tensor<T>, _rng: Option<&mut dyn Rng>) -> Result<(Tensor<T>, Saved<T>), NetworkError> {
+        let y = self.weighted_sum(&Dense::with_bias(&inputs.batch_matrix()?)?)?;
+
+        return Ok((Tensor::from_matrix(&self.activate(&y)), Saved::tensors(vec![Tensor::from_matrix(&y)])));
+    }
+
+    fn backward(&self, inputs: &Tensor<T>, saved: &Saved<T>, error: &Tensor<T>) -> Result<Gradients<T>, NetworkError> {
+        let r = self.activation.backward(&saved.tensors[0].to_matrix()?, &error.batch_matrix()?)?;
+
+        return self.gradients(inputs, &r);
+    }
+
+    fn output_backward(&self, inputs: &Tensor<T>, saved: &Saved<T>, outputs: &Tensor<T>, expected: &Tensor<T>, loss: &dyn Loss<T>) -> Result<Gradients<T>, NetworkError> {
+        let r = self.activation.output_delta(&saved.tensors[0].to_matrix()?, &outputs.batch_matrix()?, &expected.batch_matrix()?, loss)?;
+
+        return self.gradients(inputs, &r);
+    }
+
+    fn parameters(&self) -> Vec<&Matrix<T>> {
+        return vec![&self.weights];
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        return vec![&mut self.weights];
+    }
+
+    fn name(&self) -> &'static str {
+        return "dense";
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
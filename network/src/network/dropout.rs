@@ -0,0 +1,87 @@
+use std::any::Any;
+use rand::Rng;
+use crate::network::layer::{Gradients, Layer, Saved};
+use crate::network::matrix::Element;
+use crate::network::tensor::Tensor;
+use crate::network::NetworkError;
+
+// While training each input is zeroed with the given chance and the rest are scaled up to keep
+// the same expected total. Outside training the inputs go through untouched
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dropout {
+    rate: f32,
+}
+
+impl Dropout {
+    pub fn create(rate: f32) -> Dropout {
+        assert!((0. ..1.).contains(&rate), "Dropout rate {} needs to be at least 0 and below 1", rate);
+
+        return Dropout {
+            rate,
+        };
+    }
+
+    pub fn rate(&self) -> f32 {
+        return self.rate;
+    }
+}
+
+impl<T: Element> Layer<T> for Dropout {
+    fn output_shape(&self, input: &[usize]) -> Vec<usize> {
+        return input.to_vec();
+    }
+
+    fn forward(&self, inputs: &Tensor<T>, rng: Option<&mut dyn Rng>) -> Result<(Tensor<T>, Saved<T>), NetworkError> {
+        let rng = match rng {
+            Some(rng) => rng,
+            None => return Ok((inputs.clone(), Saved::none())),
+        };
+        let kept = T::from_f32(1. / (1. - self.rate));
+        let mask = (0..inputs.len()).map(|_| if rng.next_f32() < self.rate { T::zero() } else { kept }).collect::<Vec<T>>();
+        let outputs = inputs.to_vec().iter().zip(mask.iter()).map(|(x, m)| *x * *m).collect::<Vec<T>>();
+
+        return Ok((Tensor::create(inputs.shape().to_vec(), outputs)?, Saved::tensors(vec![Tensor::create(inputs.shape().to_vec(), mask)?])));
+    }
+
+    fn backward(&self, inputs: &Tensor<T>, saved: &Saved<T>, error: &Tensor<T>) -> Result<Gradients<T>, NetworkError> {
+        let input_error = match saved.tensors.first() {
+            Some(mask) => error.to_vec().iter().zip(mask.to_vec()).map(|(e, m)| *e * m).collect::<Vec<T>>(),
+            None => error.to_vec(),
+        };
+
+        return Ok(Gradients { inputs: Tensor::create(inputs.shape().to_vec(), input_error)?, parameters: vec![] });
+    }
+
+    fn name(&self) -> &'static str {
+        return "dropout";
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{ChaChaRng, SeedableRng};
+    use crate::network::dropout::Dropout;
+    use crate::network::layer::Layer;
+    use crate::network::tensor::Tensor;
+
+    #[test]
+    fn drops_only_while_training() {
+        let dropout = Dropout::create(0.25);
+        let inputs: Tensor = Tensor::create(vec![4, 100], vec![2.; 400]).unwrap();
+        let (outputs, saved) = dropout.forward(&inputs, Some(&mut ChaChaRng::from_seed(&[1]))).unwrap();
+        let dropped = outputs.to_vec().iter().filter(|v| **v == 0.).count();
+
+        assert!(dropped > 70 && dropped < 130, "dropped {}", dropped);
+        assert!(outputs.to_vec().iter().all(|v| *v == 0. || (*v - 8. / 3.).abs() < 0.00001));
+
+        // Only the inputs that made it through pass their error back
+        let error = dropout.backward(&inputs, &saved, &Tensor::create(vec![4, 100], vec![1.; 400]).unwrap()).unwrap().inputs;
+        assert_eq!(error.to_vec().iter().zip(outputs.to_vec()).filter(|(e, o)| (**e == 0.) != (*o == 0.)).count(), 0);
+
+        assert_eq!(dropout.forward(&inputs, None).unwrap().0, inputs);
+    }
+}
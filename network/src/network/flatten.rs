@@ -0,0 +1,58 @@
+use std::any::Any;
+use rand::Rng;
+use crate::network::layer::{Gradients, Layer, Saved};
+use crate::network::matrix::Element;
+use crate::network::tensor::Tensor;
+use crate::network::NetworkError;
+
+// Turns each sample into a single row of everything in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flatten {}
+
+impl<T: Element> Layer<T> for Flatten {
+    fn output_shape(&self, input: &[usize]) -> Vec<usize> {
+        return vec![input.iter().product()];
+    }
+
+    fn forward(&self, inputs: &Tensor<T>, _rng: Option<&mut dyn Rng>) -> Result<(Tensor<T>, Saved<T>), NetworkError> {
+        let batch = inputs.shape().first().copied().unwrap_or(1);
+
+        return Ok((inputs.reshape(vec![batch, inputs.len() / batch.max(1)])?, Saved::none()));
+    }
+
+    fn backward(&self, inputs: &Tensor<T>, _saved: &Saved<T>, error: &Tensor<T>) -> Result<Gradients<T>, NetworkError> {
+        return Ok(Gradients { inputs: error.reshape(inputs.shape().to_vec())?, parameters: vec![] });
+    }
+
+    fn name(&self) -> &'static str {
+        return "flatten";
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::flatten::Flatten;
+    use crate::network::layer::{Layer, Saved};
+    use crate::network::tensor::Tensor;
+
+    #[test]
+    fn flattens_each_sample() {
+        let inputs: Tensor = Tensor::create(vec![2, 3, 2, 2], (0..24).map(|v| v as f32).collect::<Vec<f32>>()).unwrap();
+        let (outputs, saved) = Flatten {}.forward(&inputs, None).unwrap();
+
+        assert_eq!(Layer::<f32>::output_shape(&Flatten {}, &[3, 2, 2]), vec![12]);
+        assert_eq!(outputs.shape(), &[2, 12]);
+        assert_eq!(outputs.to_vec(), inputs.to_vec());
+
+        let error = Tensor::create(vec![2, 12], (0..24).map(|v| v as f32 * 2.).collect::<Vec<f32>>()).unwrap();
+        let input_error = Flatten {}.backward(&inputs, &saved, &error).unwrap().inputs;
+
+        assert_eq!(input_error.shape(), &[2, 3, 2, 2]);
+        assert_eq!(input_error.get(&[1, 2, 1, 0]), 44.);
+        assert!(Layer::<f32>::backward(&Flatten {}, &inputs, &Saved::none(), &Tensor::zeros(vec![2, 11])).is_err());
+    }
+}
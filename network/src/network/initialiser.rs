@@ -0,0 +1,105 @@
+use rand::Rng;
+use crate::network::matrix::Matrix;
+
+pub use self::constant::Constant;
+pub use self::he::{HeNormal, HeUniform};
+pub use self::lecun::{LecunNormal, LecunUniform};
+pub use self::orthogonal::Orthogonal;
+pub use self::uniform::Uniform;
+pub use self::xavier::{XavierNormal, XavierUniform};
+pub use self::zeros::Zeros;
+
+mod constant;
+mod he;
+mod lecun;
+mod orthogonal;
+mod uniform;
+mod xavier;
+mod zeros;
+
+// Produces a num_of_inputs x num_of_nodes matrix, the same shape as a layer's weights without the bias row
+pub trait Initialiser {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, rng: &mut dyn Rng) -> Matrix;
+}
+
+fn uniform(num_of_inputs: usize, num_of_nodes: usize, limit: f32, rng: &mut dyn Rng) -> Matrix {
+    return Matrix::random_uniform(num_of_nodes, num_of_inputs, -limit, limit, rng);
+}
+
+fn normal(num_of_inputs: usize, num_of_nodes: usize, standard_deviation: f32, rng: &mut dyn Rng) -> Matrix {
+    return Matrix::random_normal(num_of_nodes, num_of_inputs, 0., standard_deviation, rng);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::initialiser::{Constant, HeNormal, HeUniform, Initialiser, LecunNormal, LecunUniform, Orthogonal, Uniform, XavierNormal, XavierUniform, Zeros};
+    use rand::{ChaChaRng, SeedableRng};
+    use crate::network::matrix::Matrix;
+
+    fn assert_within(initialiser: &dyn Initialiser, limit: f32) {
+        let m = initialiser.initialise(30, 20, &mut ChaChaRng::from_seed(&[1]));
+
+        assert_eq!(m.rows(), 30);
+        assert_eq!(m.cols(), 20);
+        assert!(m.elements().iter().all(|e| e.abs() <= limit));
+        assert!(m.elements().iter().any(|e| *e != 0.));
+    }
+
+    fn assert_standard_deviation(initialiser: &dyn Initialiser, expected: f32) {
+        let m = initialiser.initialise(200, 100, &mut ChaChaRng::from_seed(&[2]));
+        let n = m.elements().len() as f32;
+        let mean = m.sum() / n;
+        let variance = m.elements().iter().map(|e| (e - mean) * (e - mean)).sum::<f32>() / n;
+
+        assert_eq!(m.rows(), 200);
+        assert_eq!(m.cols(), 100);
+        assert!((variance.sqrt() - expected).abs() < expected * 0.05, "expected {} got {}", expected, variance.sqrt());
+    }
+
+    #[test]
+    fn uniform() {
+        assert_within(&Uniform::create(-0.5, 0.5), 0.5);
+        assert_within(&XavierUniform {}, (6f32 / 50.).sqrt());
+        assert_within(&HeUniform {}, (6f32 / 30.).sqrt());
+        assert_within(&LecunUniform {}, (3f32 / 30.).sqrt());
+    }
+
+    #[test]
+    fn normal() {
+        assert_standard_deviation(&XavierNormal {}, (2f32 / 300.).sqrt());
+        assert_standard_deviation(&HeNormal {}, (2f32 / 200.).sqrt());
+        assert_standard_deviation(&LecunNormal {}, (1f32 / 200.).sqrt());
+    }
+
+    #[test]
+    fn constant() {
+        assert_eq!(Zeros {}.initialise(1, 3, &mut ChaChaRng::from_seed(&[3])), Matrix::from_vec(vec![0., 0., 0.]));
+        assert_eq!(Constant::create(0.1).initialise(1, 3, &mut ChaChaRng::from_seed(&[3])), Matrix::from_vec(vec![0.1, 0.1, 0.1]));
+    }
+
+    #[test]
+    fn orthogonal() {
+        for (inputs, nodes) in [(6, 4), (4, 6), (5, 5)] {
+            let m = Orthogonal::create(1.).initialise(inputs, nodes, &mut ChaChaRng::from_seed(&[4]));
+            let product = match inputs >= nodes {
+                true => Matrix::matrix_multiplication(&Matrix::transposition(&m), &m).unwrap(),
+                false => Matrix::matrix_multiplication(&m, &Matrix::transposition(&m)).unwrap(),
+            };
+            let identity: Matrix = Matrix::identity(inputs.min(nodes));
+
+            for i in 0..identity.elements().len() {
+                assert!((product.elements()[i] - identity.elements()[i]).abs() < 0.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_weights() {
+        let m1 = HeNormal {}.initialise(4, 3, &mut ChaChaRng::from_seed(&[5]));
+        let m2 = HeNormal {}.initialise(4, 3, &mut ChaChaRng::from_seed(&[5]));
+        let m3 = HeNormal {}.initialise(4, 3, &mut ChaChaRng::from_seed(&[6]));
+
+        assert_eq!(m1, m2);
+        assert_ne!(m1, m3);
+    }
+}
@@ -0,0 +1,21 @@
+use rand::Rng;
+use crate::network::initialiser::Initialiser;
+use crate::network::matrix::Matrix;
+
+pub struct Constant {
+    pub value: f32,
+}
+
+impl Constant {
+    pub fn create(value: f32) -> Constant {
+        return Constant {
+            value,
+        };
+    }
+}
+
+impl Initialiser for Constant {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, _rng: &mut dyn Rng) -> Matrix {
+        return Matrix::full(num_of_nodes, num_of_inputs, self.value);
+    }
+}
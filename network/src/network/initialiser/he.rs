@@ -0,0 +1,24 @@
+use rand::Rng;
+use crate::network::initialiser::{normal, uniform, Initialiser};
+use crate::network::matrix::Matrix;
+
+// Kaiming, doubles the variance to make up for ReLU zeroing half its inputs
+pub struct HeUniform {
+
+}
+
+pub struct HeNormal {
+
+}
+
+impl Initialiser for HeUniform {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, rng: &mut dyn Rng) -> Matrix {
+        return uniform(num_of_inputs, num_of_nodes, (6. / (num_of_inputs as f32)).sqrt(), rng);
+    }
+}
+
+impl Initialiser for HeNormal {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, rng: &mut dyn Rng) -> Matrix {
+        return normal(num_of_inputs, num_of_nodes, (2. / (num_of_inputs as f32)).sqrt(), rng);
+    }
+}
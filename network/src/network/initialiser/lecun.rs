@@ -0,0 +1,24 @@
+use rand::Rng;
+use crate::network::initialiser::{normal, uniform, Initialiser};
+use crate::network::matrix::Matrix;
+
+// Variance of 1 / inputs. Suits SELU and other self-normalising activations
+pub struct LecunUniform {
+
+}
+
+pub struct LecunNormal {
+
+}
+
+impl Initialiser for LecunUniform {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, rng: &mut dyn Rng) -> Matrix {
+        return uniform(num_of_inputs, num_of_nodes, (3. / (num_of_inputs as f32)).sqrt(), rng);
+    }
+}
+
+impl Initialiser for LecunNormal {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, rng: &mut dyn Rng) -> Matrix {
+        return normal(num_of_inputs, num_of_nodes, (1. / (num_of_inputs as f32)).sqrt(), rng);
+    }
+}
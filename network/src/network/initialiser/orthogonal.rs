@@ -0,0 +1,54 @@
+use rand::Rng;
+use crate::network::initialiser::{normal, Initialiser};
+use crate::network::matrix::Matrix;
+
+// Orthonormalises a random normal matrix along its shorter side, then scales it by gain
+pub struct Orthogonal {
+    pub gain: f32,
+}
+
+impl Orthogonal {
+    pub fn create(gain: f32) -> Orthogonal {
+        return Orthogonal {
+            gain,
+        };
+    }
+}
+
+impl Initialiser for Orthogonal {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, rng: &mut dyn Rng) -> Matrix {
+        // Gram-Schmidt works on rows so a tall matrix is handled through its transpose
+        let tall = num_of_inputs > num_of_nodes;
+        let mut m = normal(num_of_inputs, num_of_nodes, 1., rng);
+
+        if tall {
+            m = Matrix::transposition(&m);
+        }
+
+        let cols = m.cols();
+
+        for i in 0..m.rows() {
+            for j in 0..i {
+                let dot = (0..cols).map(|k| m.get(k, i) * m.get(k, j)).sum::<f32>();
+
+                for k in 0..cols {
+                    let value = m.get(k, i) - dot * m.get(k, j);
+                    m.set(k, i, value);
+                }
+            }
+
+            let length = (0..cols).map(|k| m.get(k, i) * m.get(k, i)).sum::<f32>().sqrt();
+
+            for k in 0..cols {
+                let value = m.get(k, i) / length;
+                m.set(k, i, value);
+            }
+        }
+
+        if tall {
+            m = Matrix::transposition(&m);
+        }
+
+        return Matrix::scalar_multiplication(&m, self.gain);
+    }
+}
@@ -0,0 +1,23 @@
+use rand::Rng;
+use crate::network::initialiser::Initialiser;
+use crate::network::matrix::Matrix;
+
+pub struct Uniform {
+    pub low: f32,
+    pub high: f32,
+}
+
+impl Uniform {
+    pub fn create(low: f32, high: f32) -> Uniform {
+        return Uniform {
+            low,
+            high,
+        };
+    }
+}
+
+impl Initialiser for Uniform {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, rng: &mut dyn Rng) -> Matrix {
+        return Matrix::random_uniform(num_of_nodes, num_of_inputs, self.low, self.high, rng);
+    }
+}
@@ -0,0 +1,24 @@
+use rand::Rng;
+use crate::network::initialiser::{normal, uniform, Initialiser};
+use crate::network::matrix::Matrix;
+
+// Glorot, keeps the variance the same going forwards and backwards. Suits sigmoid and tanh
+pub struct XavierUniform {
+
+}
+
+pub struct XavierNormal {
+
+}
+
+impl Initialiser for XavierUniform {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, rng: &mut dyn Rng) -> Matrix {
+        return uniform(num_of_inputs, num_of_nodes, (6. / ((num_of_inputs + num_of_nodes) as f32)).sqrt(), rng);
+    }
+}
+
+impl Initialiser for XavierNormal {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, rng: &mut dyn Rng) -> Matrix {
+        return normal(num_of_inputs, num_of_nodes, (2. / ((num_of_inputs + num_of_nodes) as f32)).sqrt(), rng);
+    }
+}
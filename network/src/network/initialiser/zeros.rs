@@ -0,0 +1,13 @@
+use rand::Rng;
+use crate::network::initialiser::Initialiser;
+use crate::network::matrix::Matrix;
+
+pub struct Zeros {
+
+}
+
+impl Initialiser for Zeros {
+    fn initialise(&self, num_of_inputs: usize, num_of_nodes: usize, _rng: &mut dyn Rng) -> Matrix {
+        return Matrix::zeros(num_of_nodes, num_of_inputs);
+    }
+}
@@ -1,29 +1,90 @@
-use rand::random;
-use crate::network::activation::relu::Relu;
-use crate::network::matrix::Matrix;
+use std::any::Any;
+use rand::Rng;
+use crate::network::loss::Loss;
+use crate::network::matrix::{Element, Matrix};
+use crate::network::tensor::Tensor;
+use crate::network::NetworkError;
 
-pub struct Layer {
-    pub weights: Matrix,
+// A step of the network. Inputs and outputs have the batch along the first axis, and a layer
+// reshapes what it is given when it needs samples of a particular shape, so a dense layer takes
+// the output of a convolution as it is
+pub trait Layer<T: Element = f32>: Send + Sync {
+    // The shape of one sample's output for one sample of this shape
+    fn output_shape(&self, input: &[usize]) -> Vec<usize>;
+
+    // The rng is only given while training, which is when layers like dropout act. Anything
+    // backward needs from the pass goes in the saved
+    fn forward(&self, inputs: &Tensor<T>, rng: Option<&mut dyn Rng>) -> Result<(Tensor<T>, Saved<T>), NetworkError>;
+
+    // error is the gradient of the loss with respect to the outputs
+    fn backward(&self, inputs: &Tensor<T>, saved: &Saved<T>, error: &Tensor<T>) -> Result<Gradients<T>, NetworkError>;
+
+    // backward for the output layer, which is where an activation and loss can cancel out
+    fn output_backward(&self, inputs: &Tensor<T>, saved: &Saved<T>, outputs: &Tensor<T>, expected: &Tensor<T>, loss: &dyn Loss<T>) -> Result<Gradients<T>, NetworkError> {
+        let error = loss.gradient(&outputs.batch_matrix()?, &expected.batch_matrix()?);
+
+        return self.backward(inputs, saved, &Tensor::create(outputs.shape().to_vec(), error.into_elements())?);
+    }
+
+    // In the same order backward gives their gradients
+    fn parameters(&self) -> Vec<&Matrix<T>> {
+        return vec![];
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        return vec![];
+    }
+
+    // The bytes of the largest matrix the layer lowers a batch of this size to
+    fn im2col_bytes(&self, _batch: usize) -> usize {
+        return 0;
+    }
+
+    fn name(&self) -> &'static str;
+
+    // Lets the network get back to the concrete layer to save or convert it
+    fn as_any(&self) -> &dyn Any;
+}
+
+// What a layer keeps from forward for backward
+#[derive(Debug, Clone)]
+pub struct Saved<T: Element = f32> {
+    pub tensors: Vec<Tensor<T>>,
+    pub positions: Vec<usize>,
 }
 
-impl Layer {
-    pub fn create(num_of_nodes: usize, num_of_inputs: usize) -> Layer {
-        let weights = (0..((num_of_inputs + 1) * num_of_nodes)).map(|_| {
-            return (random::<f32>() * 2.) - 1.;
-        }).collect::<Vec<f32>>();
+pub struct Gradients<T: Element = f32> {
+    pub inputs: Tensor<T>,
+    pub parameters: Vec<Matrix<T>>,
+}
 
-        return Layer {
-            weights: Matrix::create(num_of_nodes, num_of_inputs + 1, weights),
+impl<T: Element> Saved<T> {
+    pub fn none() -> Saved<T> {
+        return Saved {
+            tensors: vec![],
+            positions: vec![],
         };
     }
 
-    pub fn feed_forward(&self, inputs: Matrix) -> Matrix {
-        let inputs_with_bias = Matrix::extend_rows(&inputs, vec![1.]).unwrap();
-
-        return Relu::activate(&Matrix::matrix_multiplication(&inputs_with_bias, &self.weights).unwrap());
+    pub fn tensors(tensors: Vec<Tensor<T>>) -> Saved<T> {
+        return Saved {
+            tensors,
+            positions: vec![],
+        };
     }
 
-    pub fn adjust_weights(&mut self, adjustment: &Matrix) -> () {
-        self.weights = Matrix::addition(&self.weights, adjustment).unwrap();
+    pub fn positions(positions: Vec<usize>) -> Saved<T> {
+        return Saved {
+            tensors: vec![],
+            positions,
+        };
     }
 }
+
+// Reads each sample of the inputs as this shape
+pub fn samples<T: Element>(inputs: &Tensor<T>, shape: &[usize]) -> Result<Tensor<T>, NetworkError> {
+    let mut batch_shape = vec![inputs.shape().first().copied().unwrap_or(0)];
+    batch_shape.extend_from_slice(shape);
+
+    return Ok(inputs.reshape(batch_shape)?);
+}
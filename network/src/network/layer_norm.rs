@@ -0,0 +1,164 @@
+use std::any::Any;
+use rand::Rng;
+use crate::network::layer::{Gradients, Layer, Saved};
+use crate::network::matrix::{Element, Matrix};
+use crate::network::tensor::Tensor;
+use crate::network::NetworkError;
+
+const EPSILON: f32 = 0.00001;
+
+// Normalises each sample to a mean of 0 and variance of 1 across all its features, then scales
+// and shifts every feature by a learned gamma and beta
+pub struct LayerNorm<T: Element = f32> {
+    pub gamma: Matrix<T>,
+    pub beta: Matrix<T>,
+}
+
+impl<T: Element> LayerNorm<T> {
+    // Starts with a gamma of 1 and beta of 0, so only normalising
+    pub fn create(features: usize) -> LayerNorm<T> {
+        return LayerNorm {
+            gamma: Matrix::ones(features, 1),
+            beta: Matrix::zeros(features, 1),
+        };
+    }
+
+    pub fn convert<U: Element>(&self) -> LayerNorm<U> {
+        return LayerNorm {
+            gamma: self.gamma.convert::<U>(),
+            beta: self.beta.convert::<U>(),
+        };
+    }
+}
+
+impl<T: Element> Layer<T> for LayerNorm<T> {
+    fn output_shape(&self, input: &[usize]) -> Vec<usize> {
+        return input.to_vec();
+    }
+
+    // norm = (x - mean) / sqrt(variance + epsilon)
+    // y = gamma * norm + beta
+    fn forward(&self, inputs: &Tensor<T>, _rng: Option<&mut dyn Rng>) -> Result<(Tensor<T>, Saved<T>), NetworkError> {
+        let x = inputs.batch_matrix()?;
+        let features = T::from_f32(x.cols() as f32);
+        let mut normalised = x.clone();
+        let mut inverse_deviations = vec![];
+
+        for row in normalised.elements_mut().chunks_mut(x.cols().max(1)) {
+            let mean = row.iter().fold(T::zero(), |sum, v| sum + *v) / features;
+            let variance = row.iter().fold(T::zero(), |sum, v| sum + (*v - mean) * (*v - mean)) / features;
+            let inverse_deviation = T::one() / (variance + T::from_f32(EPSILON)).sqrt();
+
+            for v in row.iter_mut() {
+                *v = (*v - mean) * inverse_deviation;
+            }
+
+            inverse_deviations.push(inverse_deviation);
+        }
+
+        let outputs = Matrix::addition(&Matrix::hadamard(&normalised, &self.gamma)?, &self.beta)?;
+        let saved = vec![Tensor::from_matrix(&normalised), Tensor::create(vec![inverse_deviations.len()], inverse_deviations)?];
+
+        return Ok((Tensor::create(inputs.shape().to_vec(), outputs.into_elements())?, Saved::tensors(saved)));
+    }
+
+    // With n features and d = e * gamma, the gradient through the normalisation is
+    // dx = (n * d - sum(d) - norm * sum(d * norm)) / (n * sqrt(variance + epsilon))
+    fn backward(&self, inputs: &Tensor<T>, saved: &Saved<T>, error: &Tensor<T>) -> Result<Gradients<T>, NetworkError> {
+        let e = error.batch_matrix()?;
+        let normalised = saved.tensors[0].to_matrix()?;
+        let inverse_deviations = saved.tensors[1].to_vec();
+        let features = T::from_f32(e.cols() as f32);
+        let mut input_error = Matrix::hadamard(&e, &self.gamma)?;
+
+        for ((row, x), inverse_deviation) in input_error.elements_mut().chunks_mut(e.cols().max(1)).zip(normalised.elements().chunks(e.cols().max(1))).zip(inverse_deviations) {
+            let sum = row.iter().fold(T::zero(), |sum, d| sum + *d);
+            let projection = row.iter().zip(x.iter()).fold(T::zero(), |sum, (d, x)| sum + *d * *x);
+
+            for (d, x) in row.iter_mut().zip(x.iter()) {
+                *d = (features * *d - sum - *x * projection) * inverse_deviation / features;
+            }
+        }
+
+        return Ok(Gradients {
+            inputs: Tensor::create(inputs.shape().to_vec(), input_error.into_elements())?,
+            parameters: vec![Matrix::hadamard(&e, &normalised)?.sum_rows(), e.sum_rows()],
+        });
+    }
+
+    fn parameters(&self) -> Vec<&Matrix<T>> {
+        return vec![&self.gamma, &self.beta];
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        return vec![&mut self.gamma, &mut self.beta];
+    }
+
+    fn name(&self) -> &'static str {
+        return "layer_norm";
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::layer::Layer;
+    use crate::network::layer_norm::LayerNorm;
+    use crate::network::matrix::Matrix;
+    use crate::network::tensor::Tensor;
+
+    #[test]
+    fn normalises_each_sample() {
+        let norm: LayerNorm = LayerNorm::create(4);
+        let (outputs, _) = norm.forward(&Tensor::create(vec![2, 4], vec![1., 2., 3., 4., -10., 0., 10., 20.]).unwrap(), None).unwrap();
+
+        for row in outputs.to_vec().chunks(4) {
+            let mean = row.iter().sum::<f32>() / 4.;
+            let variance = row.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / 4.;
+
+            assert!(mean.abs() < 0.00001);
+            assert!((variance - 1.).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn backward_matches_slopes() {
+        let create = || {
+            let mut norm: LayerNorm<f64> = LayerNorm::create(3);
+            norm.gamma = Matrix::create(3, 1, vec![0.5, -1.5, 2.]).unwrap();
+            norm.beta = Matrix::create(3, 1, vec![0.1, 0.2, -0.3]).unwrap();
+
+            return norm;
+        };
+        let inputs = Tensor::create(vec![2, 3], vec![0.3, -1.2, 0.8, 2., 0.5, -0.4]).unwrap();
+        let weights = [0.7, -0.2, 1.1, 0.4, -0.9, 0.6];
+        let loss = |norm: &LayerNorm<f64>, x: &Tensor<f64>| norm.forward(x, None).unwrap().0.to_vec().iter().zip(weights.iter()).map(|(o, w)| o * w).sum::<f64>();
+        let norm = create();
+        let (_, saved) = norm.forward(&inputs, None).unwrap();
+        let gradients = norm.backward(&inputs, &saved, &Tensor::create(vec![2, 3], weights.to_vec()).unwrap()).unwrap();
+        let h = 0.000001;
+
+        for i in 0..6 {
+            let mut above = inputs.clone();
+            let mut below = inputs.clone();
+            above.set(&[i / 3, i % 3], inputs.get(&[i / 3, i % 3]) + h);
+            below.set(&[i / 3, i % 3], inputs.get(&[i / 3, i % 3]) - h);
+
+            assert!(((loss(&norm, &above) - loss(&norm, &below)) / (2. * h) - gradients.inputs.to_vec()[i]).abs() < 0.000001);
+        }
+
+        for p in 0..2 {
+            for i in 0..3 {
+                let mut above = create();
+                let mut below = create();
+                above.parameters_mut()[p].elements_mut()[i] += h;
+                below.parameters_mut()[p].elements_mut()[i] -= h;
+
+                assert!(((loss(&above, &inputs) - loss(&below, &inputs)) / (2. * h) - gradients.parameters[p].elements()[i]).abs() < 0.000001);
+            }
+        }
+    }
+}
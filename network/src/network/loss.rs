@@ -0,0 +1,90 @@
+use crate::network::matrix::{Element, Matrix};
+
+pub use self::binary_cross_entropy::BinaryCrossEntropy;
+pub use self::categorical_cross_entropy::CategoricalCrossEntropy;
+pub use self::hinge::Hinge;
+pub use self::huber::Huber;
+pub use self::mean_absolute_error::MeanAbsoluteError;
+pub use self::mean_squared_error::MeanSquaredError;
+
+mod binary_cross_entropy;
+mod categorical_cross_entropy;
+mod hinge;
+mod huber;
+mod mean_absolute_error;
+mod mean_squared_error;
+
+// Probabilities are clamped this far from 0 and 1 so logs and divisions stay finite
+pub const EPSILON: f32 = 0.0000001;
+
+// Every row is one sample. The loss is the mean over the samples while the gradient
+// is each sample's own gradient, leaving the averaging across a batch to the caller
+pub trait Loss<T: Element = f32>: Send + Sync {
+    fn loss(&self, output: &Matrix<T>, expected: &Matrix<T>) -> T;
+
+    fn gradient(&self, output: &Matrix<T>, expected: &Matrix<T>) -> Matrix<T>;
+
+    // The gradient with respect to the inputs of a softmax that produced output, for losses where
+    // the softmax Jacobian cancels out
+    fn softmax_gradient(&self, _output: &Matrix<T>, _expected: &Matrix<T>) -> Option<Matrix<T>> {
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::loss::{BinaryCrossEntropy, CategoricalCrossEntropy, Hinge, Huber, Loss, MeanAbsoluteError, MeanSquaredError};
+    use crate::network::matrix::Matrix;
+
+    fn assert_gradient_matches_slope(loss: &dyn Loss, output: Vec<f32>, expected: Vec<f32>) {
+        let h = 0.001;
+        let o = Matrix::from_vec(output);
+        let t = Matrix::from_vec(expected);
+        let gradient = loss.gradient(&o, &t);
+
+        for i in 0..o.elements().len() {
+            let mut above = Matrix::from_vec(o.elements().to_vec());
+            let mut below = Matrix::from_vec(o.elements().to_vec());
+            above.elements_mut()[i] += h;
+            below.elements_mut()[i] -= h;
+            let slope = (loss.loss(&above, &t) - loss.loss(&below, &t)) / (2. * h);
+
+            assert!((slope - gradient.elements()[i]).abs() < 0.01, "expected {} got {}", slope, gradient.elements()[i]);
+        }
+    }
+
+    #[test]
+    fn gradients() {
+        assert_gradient_matches_slope(&MeanSquaredError {}, vec![0.2, -0.5, 1.5], vec![0., 0.5, 1.]);
+        assert_gradient_matches_slope(&MeanAbsoluteError {}, vec![0.2, -0.5, 1.5], vec![0., 0.5, 1.]);
+        assert_gradient_matches_slope(&Huber::create(1.), vec![0.2, -0.5, 3.5], vec![0., 0.5, 1.]);
+        assert_gradient_matches_slope(&BinaryCrossEntropy {}, vec![0.2, 0.6, 0.9], vec![0., 1., 1.]);
+        assert_gradient_matches_slope(&CategoricalCrossEntropy {}, vec![0.2, 0.7, 0.1], vec![0., 1., 0.]);
+        assert_gradient_matches_slope(&Hinge {}, vec![0.2, -0.5, 1.5], vec![1., -1., 1.]);
+    }
+
+    #[test]
+    fn mean_squared_error() {
+        let output = Matrix::create(2, 2, vec![1., 2., 3., 4.]).unwrap();
+        let expected = Matrix::create(2, 2, vec![1., 0., 3., 3.]).unwrap();
+
+        assert_eq!(MeanSquaredError {}.loss(&output, &expected), 1.25);
+    }
+
+    #[test]
+    fn categorical_cross_entropy() {
+        let output = Matrix::create(2, 2, vec![0.5, 0.5, 0.25, 0.75]).unwrap();
+        let expected = Matrix::create(2, 2, vec![1., 0., 0., 1.]).unwrap();
+        let result = CategoricalCrossEntropy {}.loss(&output, &expected);
+
+        assert!((result - ((-(0.5f32.ln()) - 0.75f32.ln()) / 2.)).abs() < 0.000001);
+    }
+
+    #[test]
+    fn hinge() {
+        let output: Matrix = Matrix::from_vec(vec![2., 0.5, -0.5]);
+        let expected = Matrix::from_vec(vec![1., 1., 1.]);
+
+        assert_eq!(Hinge {}.loss(&output, &expected), 0.6666667);
+    }
+}
@@ -0,0 +1,33 @@
+use crate::network::loss::{EPSILON, Loss};
+use crate::network::matrix::{Element, Matrix};
+
+// Expects every output to be an independent probability, such as from a sigmoid
+pub struct BinaryCrossEntropy {
+
+}
+
+impl<T: Element> Loss<T> for BinaryCrossEntropy {
+    fn loss(&self, output: &Matrix<T>, expected: &Matrix<T>) -> T {
+        let (low, high) = (T::from_f32(EPSILON), T::one() - T::from_f32(EPSILON));
+        let mut total = T::zero();
+
+        for (o, t) in output.elements().iter().zip(expected.elements().iter()) {
+            let p = o.max(low).min(high);
+
+            total -= *t * p.ln() + (T::one() - *t) * (T::one() - p).ln();
+        }
+
+        return total / T::from_f32(output.elements().len() as f32);
+    }
+
+    fn gradient(&self, output: &Matrix<T>, expected: &Matrix<T>) -> Matrix<T> {
+        let (low, high) = (T::from_f32(EPSILON), T::one() - T::from_f32(EPSILON));
+        let n = T::from_f32(output.cols() as f32);
+
+        return Matrix::from_fn(output.cols(), output.rows(), |col, row| {
+            let (p, t) = (output.get(col, row).max(low).min(high), expected.get(col, row));
+
+            return (p - t) / (p * (T::one() - p) * n);
+        });
+    }
+}
@@ -0,0 +1,32 @@
+use crate::network::loss::{EPSILON, Loss};
+use crate::network::matrix::{Element, Matrix};
+
+// Expects each row to be a probability distribution, such as from a softmax, against one-hot targets
+pub struct CategoricalCrossEntropy {
+
+}
+
+impl<T: Element> Loss<T> for CategoricalCrossEntropy {
+    fn loss(&self, output: &Matrix<T>, expected: &Matrix<T>) -> T {
+        let epsilon = T::from_f32(EPSILON);
+        let mut total = T::zero();
+
+        for (o, t) in output.elements().iter().zip(expected.elements().iter()) {
+            total -= *t * o.max(epsilon).ln();
+        }
+
+        return total / T::from_f32(output.rows() as f32);
+    }
+
+    fn gradient(&self, output: &Matrix<T>, expected: &Matrix<T>) -> Matrix<T> {
+        let epsilon = T::from_f32(EPSILON);
+
+        return Matrix::from_fn(output.cols(), output.rows(), |col, row| {
+            return -expected.get(col, row) / output.get(col, row).max(epsilon);
+        });
+    }
+
+    fn softmax_gradient(&self, output: &Matrix<T>, expected: &Matrix<T>) -> Option<Matrix<T>> {
+        return Some(Matrix::subtraction(output, expected).unwrap());
+    }
+}
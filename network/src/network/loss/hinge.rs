@@ -0,0 +1,29 @@
+use crate::network::loss::Loss;
+use crate::network::matrix::{Element, Matrix};
+
+// Expects targets of -1 or 1
+pub struct Hinge {
+
+}
+
+impl<T: Element> Loss<T> for Hinge {
+    fn loss(&self, output: &Matrix<T>, expected: &Matrix<T>) -> T {
+        let margins = Matrix::hadamard(output, expected).unwrap();
+
+        return Matrix::map(&margins, |v| (T::one() - *v).max(T::zero())).sum() / T::from_f32(output.elements().len() as f32);
+    }
+
+    fn gradient(&self, output: &Matrix<T>, expected: &Matrix<T>) -> Matrix<T> {
+        let n = T::from_f32(output.cols() as f32);
+
+        return Matrix::from_fn(output.cols(), output.rows(), |col, row| {
+            let (o, t) = (output.get(col, row), expected.get(col, row));
+
+            return if o * t < T::one() {
+                -t / n
+            } else {
+                T::zero()
+            }
+        });
+    }
+}
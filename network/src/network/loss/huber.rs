@@ -0,0 +1,45 @@
+use crate::network::loss::Loss;
+use crate::network::matrix::{Element, Matrix};
+
+// Squared error for differences within delta, absolute error beyond it
+pub struct Huber {
+    pub delta: f32,
+}
+
+impl Huber {
+    pub fn create(delta: f32) -> Huber {
+        return Huber {
+            delta,
+        };
+    }
+}
+
+impl<T: Element> Loss<T> for Huber {
+    fn loss(&self, output: &Matrix<T>, expected: &Matrix<T>) -> T {
+        let (delta, half) = (T::from_f32(self.delta), T::from_f32(0.5));
+        let e = Matrix::subtraction(output, expected).unwrap();
+        let losses = Matrix::map(&e, |v| {
+            return if v.abs() <= delta {
+                half * *v * *v
+            } else {
+                delta * (v.abs() - half * delta)
+            }
+        });
+
+        return losses.sum() / T::from_f32(e.elements().len() as f32);
+    }
+
+    fn gradient(&self, output: &Matrix<T>, expected: &Matrix<T>) -> Matrix<T> {
+        let delta = T::from_f32(self.delta);
+        let e = Matrix::subtraction(output, expected).unwrap();
+        let n = T::from_f32(e.cols() as f32);
+
+        return Matrix::map(&e, |v| {
+            return if v.abs() <= delta {
+                *v / n
+            } else {
+                delta * v.signum() / n
+            }
+        });
+    }
+}
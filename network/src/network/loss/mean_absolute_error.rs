@@ -0,0 +1,27 @@
+use crate::network::loss::Loss;
+use crate::network::matrix::{Element, Matrix};
+
+pub struct MeanAbsoluteError {
+
+}
+
+impl<T: Element> Loss<T> for MeanAbsoluteError {
+    fn loss(&self, output: &Matrix<T>, expected: &Matrix<T>) -> T {
+        let e = Matrix::subtraction(output, expected).unwrap();
+
+        return Matrix::map(&e, |v| v.abs()).sum() / T::from_f32(e.elements().len() as f32);
+    }
+
+    fn gradient(&self, output: &Matrix<T>, expected: &Matrix<T>) -> Matrix<T> {
+        let e = Matrix::subtraction(output, expected).unwrap();
+        let n = T::from_f32(e.cols() as f32);
+
+        return Matrix::map(&e, |v| {
+            return if *v == T::zero() {
+                T::zero()
+            } else {
+                v.signum() / n
+            }
+        });
+    }
+}
@@ -1,14 +1,80 @@
 use std::fmt::{Display, Formatter};
-
-#[derive(Debug)]
-pub struct Matrix {
-    pub cols: usize,
-    pub rows: usize,
-    pub elements: Vec<f32>,
+use rand::Rng;
+use crate::network::parallel;
+
+pub use self::element::Element;
+pub use self::error::MatrixError;
+pub use self::half::{Bf16, F16};
+
+mod element;
+mod error;
+mod half;
+mod operators;
+mod scalar;
+#[cfg(feature = "simd")]
+mod simd;
+
+const BLOCK_SIZE: usize = 64;
+
+// Below roughly this many multiply-adds or elements a thread costs more than it saves
+const PARALLEL_WORK: usize = 1 << 18;
+
+const POWER_ITERATIONS: usize = 100;
+
+// The fields are private so that elements always holds exactly cols * rows values
+#[derive(Debug, Clone)]
+pub struct Matrix<T = f32> {
+    cols: usize,
+    rows: usize,
+    elements: Vec<T>,
 }
 
-impl Matrix {
-    pub fn create(cols: usize, rows: usize, elements: Vec<f32>) -> Matrix {
+impl<T: Element> Matrix<T> {
+    pub fn create(cols: usize, rows: usize, elements: Vec<T>) -> Result<Matrix<T>, MatrixError> {
+        if elements.len() != cols * rows {
+            return Err(MatrixError::WrongNumberOfElements {
+                operation: "create",
+                shape: (cols, rows),
+                elements: elements.len(),
+            });
+        }
+
+        return Ok(Matrix {
+            cols,
+            rows,
+            elements,
+        });
+    }
+
+    pub fn zeros(cols: usize, rows: usize) -> Matrix<T> {
+        return Matrix::full(cols, rows, T::zero());
+    }
+
+    pub fn ones(cols: usize, rows: usize) -> Matrix<T> {
+        return Matrix::full(cols, rows, T::one());
+    }
+
+    pub fn full(cols: usize, rows: usize, value: T) -> Matrix<T> {
+        return Matrix {
+            cols,
+            rows,
+            elements: vec![value; cols * rows],
+        };
+    }
+
+    // Calls f with the (col, row) of every element, a row at a time
+    pub fn from_fn<F>(cols: usize, rows: usize, mut f: F) -> Matrix<T>
+    where
+        F: FnMut(usize, usize) -> T {
+
+        let mut elements = Vec::with_capacity(cols * rows);
+
+        for row in 0..rows {
+            for col in 0..cols {
+                elements.push(f(col, row));
+            }
+        }
+
         return Matrix {
             cols,
             rows,
@@ -16,11 +82,29 @@ impl Matrix {
         };
     }
 
-    pub fn identity(size: usize) -> Matrix {
-        let mut elements = vec![0.; size * size];
+    // Every element is drawn evenly from [low, high)
+    pub fn random_uniform(cols: usize, rows: usize, low: T, high: T, rng: &mut dyn Rng) -> Matrix<T> {
+        return Matrix::from_fn(cols, rows, |_, _| {
+            return low + (high - low) * T::from_f32(rng.next_f32());
+        });
+    }
+
+    pub fn random_normal(cols: usize, rows: usize, mean: T, standard_deviation: T, rng: &mut dyn Rng) -> Matrix<T> {
+        return Matrix::from_fn(cols, rows, |_, _| {
+            // Box-Muller, 1 - random keeps the log away from 0
+            let u1 = 1. - rng.next_f32();
+            let u2 = rng.next_f32();
+            let z = (-2. * u1.ln()).sqrt() * (2. * std::f32::consts::PI * u2).cos();
+
+            return T::from_f32(z) * standard_deviation + mean;
+        });
+    }
+
+    pub fn identity(size: usize) -> Matrix<T> {
+        let mut elements = vec![T::zero(); size * size];
 
         for x in 0..(size) {
-            elements[(x * size) + x] = 1.;
+            elements[(x * size) + x] = T::one();
         }
 
         return Matrix {
@@ -30,52 +114,273 @@ impl Matrix {
         };
     }
 
-    pub fn from_vec(elements: Vec<f32>) -> Matrix {
-        return Matrix::create(elements.len(), 1, elements);
+    pub fn from_vec(elements: Vec<T>) -> Matrix<T> {
+        return Matrix {
+            cols: elements.len(),
+            rows: 1,
+            elements,
+        };
+    }
+
+    // Errors when the rows aren't all the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Matrix<T>, MatrixError> {
+        let cols = rows.first().map_or(0, |r| r.len());
+
+        if rows.iter().any(|r| r.len() != cols) {
+            return Err(MatrixError::WrongNumberOfElements {
+                operation: "from_rows",
+                shape: (cols, rows.len()),
+                elements: rows.iter().map(|r| r.len()).sum(),
+            });
+        }
+
+        return Ok(Matrix {
+            cols,
+            rows: rows.len(),
+            elements: rows.concat(),
+        });
+    }
+
+    pub fn cols(&self) -> usize {
+        return self.cols;
+    }
+
+    pub fn rows(&self) -> usize {
+        return self.rows;
+    }
+
+    pub fn elements(&self) -> &[T] {
+        return &self.elements;
+    }
+
+    // The elements can be changed but not added to or removed
+    pub fn elements_mut(&mut self) -> &mut [T] {
+        return &mut self.elements;
+    }
+
+    pub fn into_elements(self) -> Vec<T> {
+        return self.elements;
     }
 
-    pub fn map<F>(m: &Matrix, mapper: F) -> Matrix
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        if self.cols == 0 {
+            return vec![vec![]; self.rows];
+        }
+
+        return self.elements.chunks(self.cols).map(|r| r.to_vec()).collect::<Vec<Vec<T>>>();
+    }
+
+    // Rounds each element to the nearest value U can hold
+    pub fn convert<U: Element>(&self) -> Matrix<U> {
+        return Matrix {
+            cols: self.cols,
+            rows: self.rows,
+            elements: self.elements.iter().map(|e| U::from_f64(e.to_f64())).collect::<Vec<U>>(),
+        };
+    }
+
+    pub fn map<F>(m: &Matrix<T>, mapper: F) -> Matrix<T>
     where
-        F: Fn(&f32) -> f32 {
+        F: Fn(&T) -> T {
 
         return Matrix {
             rows: m.rows,
             cols: m.cols,
-            elements: m.elements.iter().map(mapper).collect::<Vec<f32>>()
+            elements: T::map(&m.elements, mapper),
         };
     }
 
-    pub fn addition(m1: &Matrix, m2: &Matrix) -> Result<Matrix, MatrixAdditionOperationError> {
-        if m1.rows != m2.rows || m1.cols != m2.cols {
-            return Err(MatrixAdditionOperationError::MatricesShapesDoNotMatch);
+    // The element-wise operations broadcast: a side with a single row or column is repeated to
+    // match the other, so a 1x1 matrix acts as a scalar, a row vector is added to every row and
+    // a column vector to every column
+    pub fn addition(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        let (cols, rows) = Matrix::check_element_wise("addition", m1, m2)?;
+
+        if !m1.has_shape_of(m2) {
+            return Ok(Matrix::broadcast(m1, m2, cols, rows, |a, b| a + b));
         }
 
-        if m1.elements.len() != m2.elements.len() {
-            return Err(MatrixAdditionOperationError::MatricesHaveDifferentNumberOfElements);
+        return Ok(Matrix {
+            cols: m1.cols,
+            rows: m1.rows,
+            elements: Matrix::element_wise(m1.elements.len(), |offset, out| {
+                T::addition(&m1.elements[offset..], &m2.elements[offset..], out);
+            }),
+        });
+    }
+
+    pub fn subtraction(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        Matrix::check_element_wise("subtraction", m1, m2)?;
+
+        return Matrix::addition(m1, &Matrix::scalar_multiplication(m2, -T::one()));
+    }
+
+    pub fn scalar_multiplication(m: &Matrix<T>, s: T) -> Matrix<T> {
+        return Matrix {
+            cols: m.cols,
+            rows: m.rows,
+            elements: Matrix::element_wise(m.elements.len(), |offset, out| {
+                T::scale(&m.elements[offset..], s, out);
+            }),
+        };
+    }
+
+    // The in place versions write the result over self instead of allocating a new matrix
+    // m can be broadcast as long as the result is still the shape of self
+    pub fn add_in_place(&mut self, m: &Matrix<T>) -> Result<(), MatrixError> {
+        self.check_in_place("add_in_place", m)?;
+
+        if !self.has_shape_of(m) {
+            self.broadcast_in_place(m, |a, b| a + b);
+
+            return Ok(());
         }
 
-        let mut result_elements = vec![];
+        parallel::for_each_span(&mut self.elements, PARALLEL_WORK, |offset, out| {
+            <T as Element>::add_assign(out, &m.elements[offset..]);
+        });
+
+        return Ok(());
+    }
+
+    pub fn subtract_in_place(&mut self, m: &Matrix<T>) -> Result<(), MatrixError> {
+        self.check_in_place("subtract_in_place", m)?;
 
-        for i in 0..m1.elements.len() {
-            result_elements.push(m1.elements[i] + m2.elements[i]);
+        return self.add_scaled_in_place(m, -T::one());
+    }
+
+    // self + m * s without the intermediate matrix
+    pub fn add_scaled_in_place(&mut self, m: &Matrix<T>, s: T) -> Result<(), MatrixError> {
+        self.check_in_place("add_scaled_in_place", m)?;
+
+        if !self.has_shape_of(m) {
+            self.broadcast_in_place(m, |a, b| a + b * s);
+
+            return Ok(());
         }
 
-        return Ok(Matrix {
-            cols: m1.cols,
-            rows: m1.rows,
-            elements: result_elements,
+        parallel::for_each_span(&mut self.elements, PARALLEL_WORK, |offset, out| {
+            T::add_scaled_assign(out, &m.elements[offset..], s);
+        });
+
+        return Ok(());
+    }
+
+    pub fn scale_in_place(&mut self, s: T) -> () {
+        parallel::for_each_span(&mut self.elements, PARALLEL_WORK, |_, out| {
+            T::scale_assign(out, s);
         });
     }
 
-    pub fn subtraction(m1: &Matrix, m2: &Matrix) -> Result<Matrix, MatrixAdditionOperationError> {
-        return Matrix::addition(m1, &Matrix::scalar_multiplication(m2, -1.));
+    pub fn hadamard_in_place(&mut self, m: &Matrix<T>) -> Result<(), MatrixError> {
+        self.check_in_place("hadamard_in_place", m)?;
+
+        if !self.has_shape_of(m) {
+            self.broadcast_in_place(m, |a, b| a * b);
+
+            return Ok(());
+        }
+
+        parallel::for_each_span(&mut self.elements, PARALLEL_WORK, |offset, out| {
+            T::hadamard_assign(out, &m.elements[offset..]);
+        });
+
+        return Ok(());
+    }
+
+    pub fn divide_in_place(&mut self, m: &Matrix<T>) -> Result<(), MatrixError> {
+        self.check_in_place("divide_in_place", m)?;
+        self.broadcast_in_place(m, |a, b| a / b);
+
+        return Ok(());
+    }
+
+    pub fn map_in_place<F>(&mut self, mapper: F) -> ()
+    where
+        F: Fn(&T) -> T {
+
+        for e in self.elements.iter_mut() {
+            *e = mapper(e);
+        }
+    }
+
+    // Gives the (cols, rows) of the result
+    fn check_element_wise(operation: &'static str, m1: &Matrix<T>, m2: &Matrix<T>) -> Result<(usize, usize), MatrixError> {
+        return Matrix::broadcast_shape(m1, m2).ok_or(MatrixError::ShapesDoNotMatch {
+            operation,
+            left: (m1.cols, m1.rows),
+            right: (m2.cols, m2.rows),
+        });
+    }
+
+    fn check_in_place(&self, operation: &'static str, m: &Matrix<T>) -> Result<(), MatrixError> {
+        if Matrix::check_element_wise(operation, self, m)? != (self.cols, self.rows) {
+            return Err(MatrixError::ShapesDoNotMatch {
+                operation,
+                left: (self.cols, self.rows),
+                right: (m.cols, m.rows),
+            });
+        }
+
+        return Ok(());
+    }
+
+    // Each dimension has to match or be 1 on one side
+    fn broadcast_shape(m1: &Matrix<T>, m2: &Matrix<T>) -> Option<(usize, usize)> {
+        let dimension = |a: usize, b: usize| {
+            return match (a, b) {
+                _ if a == b => Some(a),
+                (1, _) => Some(b),
+                (_, 1) => Some(a),
+                _ => None,
+            };
+        };
+
+        return Some((dimension(m1.cols, m2.cols)?, dimension(m1.rows, m2.rows)?));
+    }
+
+    fn broadcast<F>(m1: &Matrix<T>, m2: &Matrix<T>, cols: usize, rows: usize, op: F) -> Matrix<T>
+    where
+        F: Fn(T, T) -> T {
+
+        let mut elements = Vec::with_capacity(cols * rows);
+
+        for row in 0..rows {
+            for col in 0..cols {
+                elements.push(op(m1.get_broadcast(col, row), m2.get_broadcast(col, row)));
+            }
+        }
+
+        return Matrix {
+            cols,
+            rows,
+            elements,
+        };
+    }
+
+    fn broadcast_in_place<F>(&mut self, m: &Matrix<T>, op: F) -> ()
+    where
+        F: Fn(T, T) -> T {
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let index = row * self.cols + col;
+
+                self.elements[index] = op(self.elements[index], m.get_broadcast(col, row));
+            }
+        }
+    }
+
+    // A single row or column reads the same value whatever the row or column asked for
+    fn get_broadcast(&self, col: usize, row: usize) -> T {
+        return self.get(if self.cols == 1 { 0 } else { col }, if self.rows == 1 { 0 } else { row });
     }
 
-    pub fn scalar_multiplication(m: &Matrix, s: f32) -> Matrix {
-        return Matrix::map(m, |e| e * s);
+    fn has_shape_of(&self, m: &Matrix<T>) -> bool {
+        return self.cols == m.cols && self.rows == m.rows;
     }
 
-    pub fn transposition(m: &Matrix) -> Matrix {
+    pub fn transposition(m: &Matrix<T>) -> Matrix<T> {
         let mut result_elements = vec![];
 
         for i in 0..m.cols {
@@ -93,35 +398,97 @@ impl Matrix {
         };
     }
 
-    pub fn matrix_multiplication(m1: &Matrix, m2: &Matrix) -> Result<Matrix, MatrixMultiplicationOperationError> {
-        let mut result_elements = vec![];
+    pub fn matrix_multiplication(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        let mut result = Matrix { cols: m2.cols, rows: m1.rows, elements: vec![] };
+
+        Matrix::matrix_multiplication_into(m1, m2, &mut result)?;
 
+        return Ok(result);
+    }
+
+    // Overwrites out with m1 * m2, reusing its allocation when it already has the capacity.
+    // Works through BLOCK_SIZE tiles in i-k-j order so the rows of m2 and out are read
+    // sequentially while they are still in cache. Each element still sums over k in order
+    // so the result matches the straightforward triple loop exactly
+    pub fn matrix_multiplication_into(m1: &Matrix<T>, m2: &Matrix<T>, out: &mut Matrix<T>) -> Result<(), MatrixError> {
         if m1.cols != m2.rows {
-            return Err(MatrixMultiplicationOperationError::LeftMatrixColumnsDoNotEqualRightMatrixRows);
+            return Err(MatrixError::ShapesDoNotMatch {
+                operation: "matrix_multiplication",
+                left: (m1.cols, m1.rows),
+                right: (m2.cols, m2.rows),
+            });
         }
 
-        for j in 0..m1.rows {
-            for i in 0..m2.cols {
-                let mut sum = 0.;
+        let (rows, inner, cols) = (m1.rows, m1.cols, m2.cols);
 
-                for k in 0..m1.cols {
-                    sum += m1.get(k, j) * m2.get(i, k);
-                }
+        out.cols = cols;
+        out.rows = rows;
+        out.elements.clear();
+        out.elements.resize(rows * cols, T::zero());
+
+        if cols == 0 {
+            return Ok(());
+        }
+
+        // Every output row only depends on one row of m1, so rows can be shared between threads
+        let rows_per_span = (PARALLEL_WORK / (inner * cols).max(1)).max(1);
+
+        parallel::for_each_span(&mut out.elements, rows_per_span * cols, |offset, span| {
+            Matrix::multiply_rows(m1, m2, offset / cols, span);
+        });
+
+        return Ok(());
+    }
 
-                result_elements.push(sum);
+    fn multiply_rows(m1: &Matrix<T>, m2: &Matrix<T>, first_row: usize, out: &mut [T]) -> () {
+        let (inner, cols) = (m1.cols, m2.cols);
+        let rows = out.len() / cols;
+
+        for i0 in (0..rows).step_by(BLOCK_SIZE) {
+            let i_end = (i0 + BLOCK_SIZE).min(rows);
+
+            for k0 in (0..inner).step_by(BLOCK_SIZE) {
+                let k_end = (k0 + BLOCK_SIZE).min(inner);
+
+                for j0 in (0..cols).step_by(BLOCK_SIZE) {
+                    let j_end = (j0 + BLOCK_SIZE).min(cols);
+
+                    for i in i0..i_end {
+                        let out_row = &mut out[(i * cols + j0)..(i * cols + j_end)];
+                        let m1_start = (first_row + i) * inner;
+                        let m1_row = &m1.elements[(m1_start + k0)..(m1_start + k_end)];
+
+                        for (k, a) in (k0..k_end).zip(m1_row) {
+                            let m2_row = &m2.elements[(k * cols + j0)..(k * cols + j_end)];
+
+                            for (o, b) in out_row.iter_mut().zip(m2_row) {
+                                *o += *a * *b;
+                            }
+                        }
+                    }
+                }
             }
         }
+    }
+
+    fn element_wise<F>(len: usize, kernel: F) -> Vec<T>
+    where
+        F: Fn(usize, &mut [T]) + Sync {
+
+        let mut result_elements = vec![T::zero(); len];
 
-        return Ok(Matrix::create(
-            m2.cols,
-            m1.rows,
-            result_elements,
-        ));
+        parallel::for_each_span(&mut result_elements, PARALLEL_WORK, kernel);
+
+        return result_elements;
     }
 
-    pub fn extend_columns(m: &Matrix, values: Vec<f32>) -> Result<Matrix, MatrixExtendOperationError> {
+    pub fn extend_columns(m: &Matrix<T>, values: Vec<T>) -> Result<Matrix<T>, MatrixError> {
         if values.len() < m.cols {
-            return Err(MatrixExtendOperationError::NotEnoughValuesToExtend);
+            return Err(MatrixError::NotEnoughValuesToExtend {
+                operation: "extend_columns",
+                needed: m.cols,
+                given: values.len(),
+            });
         }
 
         let mut result_elements = vec![];
@@ -130,7 +497,7 @@ impl Matrix {
             result_elements.push(*e);
         }
 
-        for v in values {
+        for v in values.into_iter().take(m.cols) {
             result_elements.push(v);
         }
 
@@ -141,25 +508,23 @@ impl Matrix {
         });
     }
 
-    pub fn extend_rows(m: &Matrix, values: Vec<f32>) -> Result<Matrix, MatrixExtendOperationError> {
+    pub fn extend_rows(m: &Matrix<T>, values: Vec<T>) -> Result<Matrix<T>, MatrixError> {
         if values.len() < m.rows {
-            return Err(MatrixExtendOperationError::NotEnoughValuesToExtend);
+            return Err(MatrixError::NotEnoughValuesToExtend {
+                operation: "extend_rows",
+                needed: m.rows,
+                given: values.len(),
+            });
         }
 
-        let mut result_elements = vec![];
-        let mut values_pointer = 0;
-
-        for x in 0..m.elements.len() {
-            if x != 0 && (x % m.cols) == 0 {
-                result_elements.push(values[values_pointer]);
-                values_pointer += 1;
-            }
+        // A row at a time so a matrix without rows or columns still ends up with one value per row
+        let mut result_elements = Vec::with_capacity((m.cols + 1) * m.rows);
 
-            result_elements.push(m.elements[x]);
+        for (row, value) in values.into_iter().take(m.rows).enumerate() {
+            result_elements.extend_from_slice(&m.elements[row * m.cols..(row + 1) * m.cols]);
+            result_elements.push(value);
         }
 
-        result_elements.push(values[values_pointer]);
-
         return Ok(Matrix {
             cols: m.cols + 1,
             rows: m.rows,
@@ -167,46 +532,198 @@ impl Matrix {
         });
     }
 
-    pub fn hadamard(m1: &Matrix, m2: &Matrix) -> Result<Matrix, MatrixHadamardOperationError> {
-        if m1.rows != m2.rows || m1.cols != m2.cols {
-            return Err(MatrixHadamardOperationError::MatricesShapesDoNotMatch);
-        }
+    pub fn hadamard(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        let (cols, rows) = Matrix::check_element_wise("hadamard", m1, m2)?;
 
-        if m1.elements.len() != m2.elements.len() {
-            return Err(MatrixHadamardOperationError::MatricesHaveDifferentNumberOfElements);
-        }
-
-        let mut result_elements = vec![];
-
-        for i in 0..m1.elements.len() {
-            result_elements.push(m1.elements[i] * m2.elements[i]);
+        if !m1.has_shape_of(m2) {
+            return Ok(Matrix::broadcast(m1, m2, cols, rows, |a, b| a * b));
         }
 
         return Ok(Matrix {
             cols: m1.cols,
             rows: m1.rows,
-            elements: result_elements,
+            elements: Matrix::element_wise(m1.elements.len(), |offset, out| {
+                T::hadamard(&m1.elements[offset..], &m2.elements[offset..], out);
+            }),
         });
     }
 
-    pub fn get(&self, col: usize, row: usize) -> f32 {
+    pub fn division(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        let (cols, rows) = Matrix::check_element_wise("division", m1, m2)?;
+
+        return Ok(Matrix::broadcast(m1, m2, cols, rows, |a, b| a / b));
+    }
+
+    pub fn get(&self, col: usize, row: usize) -> T {
         let index = row * self.cols + col;
 
         return self.elements[index];
     }
 
-    pub fn set(&mut self, col: usize, row: usize, value: f32) -> () {
+    pub fn set(&mut self, col: usize, row: usize, value: T) -> () {
         let index = row * self.cols + col;
 
         self.elements[index] = value;
     }
 
-    pub fn sum(&self) -> f32 {
-        return self.elements.iter().fold(0., |acc, e| e + acc);
+    pub fn sum(&self) -> T {
+        return T::sum(&self.elements);
+    }
+
+    // Adds the rows together into a single row
+    pub fn sum_rows(&self) -> Matrix<T> {
+        return self.reduce(Axis::Rows, T::zero(), |total, _, e| total + e);
+    }
+
+    // Adds the columns together into a single column
+    pub fn sum_cols(&self) -> Matrix<T> {
+        return self.reduce(Axis::Cols, T::zero(), |total, _, e| total + e);
+    }
+
+    pub fn mean(&self, axis: Axis) -> Matrix<T> {
+        let n = T::from_f32(self.lane_len(axis) as f32);
+        let sums = match axis {
+            Axis::Rows => self.sum_rows(),
+            Axis::Cols => self.sum_cols(),
+        };
+
+        return Matrix::map(&sums, |s| *s / n);
+    }
+
+    // The population variance, found in one pass with Welford's method so large values don't cancel out
+    pub fn variance(&self, axis: Axis) -> Matrix<T> {
+        let n = T::from_f32(self.lane_len(axis) as f32);
+        let totals = self.fold_lanes(axis, (T::zero(), T::zero(), T::zero()), |(count, mean, squares), _, e| {
+            let count = count + T::one();
+            let delta = e - mean;
+            let mean = mean + delta / count;
+
+            return (count, mean, squares + delta * (e - mean));
+        });
+
+        return self.axis_matrix(axis, totals.into_iter().map(|(_, _, squares)| squares / n).collect::<Vec<T>>());
+    }
+
+    pub fn max(&self, axis: Axis) -> Matrix<T> {
+        return self.reduce(axis, T::from_f32(f32::NEG_INFINITY), |max, _, e| max.max(e));
+    }
+
+    pub fn min(&self, axis: Axis) -> Matrix<T> {
+        return self.reduce(axis, T::from_f32(f32::INFINITY), |min, _, e| min.min(e));
+    }
+
+    // The position of the largest element along the axis, the first one when there's a tie
+    pub fn argmax(&self, axis: Axis) -> Vec<usize> {
+        return self.arg(axis, |e, best| e > best);
+    }
+
+    pub fn argmin(&self, axis: Axis) -> Vec<usize> {
+        return self.arg(axis, |e, best| e < best);
+    }
+
+    pub fn norm(&self, norm: Norm) -> T {
+        return match norm {
+            Norm::L1 => self.elements.iter().fold(T::zero(), |total, e| total + e.abs()),
+            Norm::L2 => self.spectral_norm(),
+            Norm::Frobenius => self.elements.iter().fold(T::zero(), |total, e| total + *e * *e).sqrt(),
+        };
+    }
+
+    // The sum of the element-wise products. Unlike hadamard the shapes have to match exactly
+    pub fn dot(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<T, MatrixError> {
+        if !m1.has_shape_of(m2) {
+            return Err(MatrixError::ShapesDoNotMatch {
+                operation: "dot",
+                left: (m1.cols, m1.rows),
+                right: (m2.cols, m2.rows),
+            });
+        }
+
+        return Ok(Matrix::hadamard(m1, m2)?.sum());
+    }
+
+    // The largest singular value, the square root of the largest eigenvalue of transpose(m) * m.
+    // Power iteration starts from a vector with a different share of every axis, so it can't be
+    // orthogonal to the top eigenvector the way a row of the matrix can be
+    fn spectral_norm(&self) -> T {
+        if self.elements.is_empty() {
+            return T::zero();
+        }
+
+        let gram = Matrix::matrix_multiplication(&Matrix::transposition(self), self).unwrap();
+        let mut v = Matrix::from_fn(1, self.cols, |_, row| T::from_f32(1. + (row + 1) as f32 / (self.cols + 1) as f32));
+
+        for _ in 0..POWER_ITERATIONS {
+            let next = Matrix::matrix_multiplication(&gram, &v).unwrap();
+            let length = next.norm(Norm::Frobenius);
+
+            if length == T::zero() {
+                return T::zero();
+            }
+
+            v = Matrix::map(&next, |e| *e / length);
+        }
+
+        // v is a unit eigenvector so its Rayleigh quotient is the eigenvalue
+        return Matrix::dot(&v, &Matrix::matrix_multiplication(&gram, &v).unwrap()).unwrap().sqrt();
+    }
+
+    fn reduce<F>(&self, axis: Axis, initial: T, f: F) -> Matrix<T>
+    where
+        F: Fn(T, usize, T) -> T {
+
+        return self.axis_matrix(axis, self.fold_lanes(axis, initial, f));
+    }
+
+    fn arg<F>(&self, axis: Axis, better: F) -> Vec<usize>
+    where
+        F: Fn(T, T) -> bool {
+
+        let best = self.fold_lanes(axis, None, |best: Option<(usize, T)>, i, e| {
+            return match best {
+                Some((_, b)) if !better(e, b) => best,
+                _ => Some((i, e)),
+            };
+        });
+
+        return best.into_iter().map(|b| b.map_or(0, |(i, _)| i)).collect::<Vec<usize>>();
+    }
+
+    // Folds every lane along the axis, a column for Rows and a row for Cols, passing f each
+    // element with its position in the lane
+    fn fold_lanes<A, F>(&self, axis: Axis, initial: A, f: F) -> Vec<A>
+    where
+        A: Clone,
+        F: Fn(A, usize, T) -> A {
+
+        let (lanes, stride, step) = match axis {
+            Axis::Rows => (self.cols, 1, self.cols),
+            Axis::Cols => (self.rows, self.cols, 1),
+        };
+
+        return (0..lanes).map(|lane| {
+            return (0..self.lane_len(axis)).fold(initial.clone(), |acc, i| f(acc, i, self.elements[lane * stride + i * step]));
+        }).collect::<Vec<A>>();
+    }
+
+    fn lane_len(&self, axis: Axis) -> usize {
+        return match axis {
+            Axis::Rows => self.rows,
+            Axis::Cols => self.cols,
+        };
+    }
+
+    fn axis_matrix(&self, axis: Axis, values: Vec<T>) -> Matrix<T> {
+        let len = values.len();
+
+        return match axis {
+            Axis::Rows => Matrix::from_vec(values),
+            Axis::Cols => Matrix { cols: 1, rows: len, elements: values },
+        };
     }
 }
 
-impl PartialEq for Matrix {
+impl<T: Element> PartialEq for Matrix<T> {
     fn eq(&self, other: &Self) -> bool {
         let mut result = self.cols == other.cols && self.rows == other.rows && self.elements.len() == other.elements.len();
 
@@ -218,7 +735,7 @@ impl PartialEq for Matrix {
     }
 }
 
-impl Display for Matrix {
+impl<T: Element> Display for Matrix<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut out = ("|").to_owned();
 
@@ -236,45 +753,93 @@ impl Display for Matrix {
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum MatrixAdditionOperationError {
-    MatricesShapesDoNotMatch,
-    MatricesHaveDifferentNumberOfElements,
-}
-
-#[derive(Debug, PartialEq)]
-pub enum MatrixMultiplicationOperationError {
-    LeftMatrixColumnsDoNotEqualRightMatrixRows,
-}
-
-#[derive(Debug, PartialEq)]
-pub enum MatrixExtendOperationError {
-    NotEnoughValuesToExtend,
+// The axis a reduction collapses. Reducing Rows leaves one value per column, reducing Cols one per row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Axis {
+    Rows,
+    Cols,
 }
 
-#[derive(Debug, PartialEq)]
-pub enum MatrixHadamardOperationError {
-    MatricesShapesDoNotMatch,
-    MatricesHaveDifferentNumberOfElements,
+// L1 sums the absolute values, L2 is the spectral norm, the largest singular value, and Frobenius
+// is the square root of the sum of squares. For a single row or column L2 and Frobenius agree
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Norm {
+    L1,
+    L2,
+    Frobenius,
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::network::matrix::{Matrix, MatrixAdditionOperationError, MatrixExtendOperationError, MatrixHadamardOperationError, MatrixMultiplicationOperationError};
+    use rand::{ChaChaRng, SeedableRng};
+    use crate::network::matrix::{Axis, Matrix, MatrixError, Norm};
 
     #[test]
     fn identity() {
         let m = Matrix::identity(3);
-        let expected = Matrix::create(3, 3, vec![1., 0., 0., 0., 1., 0., 0., 0., 1.]);
+        let expected = Matrix::create(3, 3, vec![1., 0., 0., 0., 1., 0., 0., 0., 1.]).unwrap();
 
         assert_eq!(m, expected);
     }
 
+    #[test]
+    fn from_rows() {
+        let m = Matrix::from_rows(vec![vec![1., 2., 3.], vec![4., 5., 6.]]).unwrap();
+        let expected = Matrix::create(3, 2, vec![1., 2., 3., 4., 5., 6.]).unwrap();
+
+        assert_eq!(m, expected);
+        assert_eq!(m.to_rows(), vec![vec![1., 2., 3.], vec![4., 5., 6.]]);
+    }
+
+    #[test]
+    fn create_checks_the_number_of_elements() {
+        assert_eq!(Matrix::create(3, 2, vec![1., 2., 3., 4., 5.]), Err(MatrixError::WrongNumberOfElements { operation: "create", shape: (3, 2), elements: 5 }));
+        assert_eq!(Matrix::from_rows(vec![vec![1., 2.], vec![3.]]), Err(MatrixError::WrongNumberOfElements { operation: "from_rows", shape: (2, 2), elements: 3 }));
+
+        // The total is right for three rows of two but the rows don't line up
+        assert_eq!(Matrix::from_rows(vec![vec![1., 2.], vec![3., 4., 5.], vec![6.]]), Err(MatrixError::WrongNumberOfElements { operation: "from_rows", shape: (2, 3), elements: 6 }));
+    }
+
+    #[test]
+    fn constructors() {
+        assert_eq!(Matrix::zeros(2, 1), Matrix::create(2, 1, vec![0., 0.]).unwrap());
+        assert_eq!(Matrix::ones(1, 2), Matrix::create(1, 2, vec![1., 1.]).unwrap());
+        assert_eq!(Matrix::full(2, 2, 0.5), Matrix::create(2, 2, vec![0.5, 0.5, 0.5, 0.5]).unwrap());
+        assert_eq!(Matrix::from_fn(3, 2, |col, row| (row * 10 + col) as f32), Matrix::create(3, 2, vec![0., 1., 2., 10., 11., 12.]).unwrap());
+    }
+
+    #[test]
+    fn random_constructors() {
+        let uniform: Matrix = Matrix::random_uniform(20, 30, -0.5, 2., &mut ChaChaRng::from_seed(&[1]));
+
+        assert_eq!((uniform.cols(), uniform.rows()), (20, 30));
+        assert!(uniform.elements().iter().all(|e| *e >= -0.5 && *e < 2.));
+        assert_eq!(uniform, Matrix::random_uniform(20, 30, -0.5, 2., &mut ChaChaRng::from_seed(&[1])));
+
+        let normal: Matrix = Matrix::random_normal(100, 200, 3., 0.5, &mut ChaChaRng::from_seed(&[2]));
+        let mean = normal.sum() / 20000.;
+        let variance = normal.elements().iter().map(|e| (e - mean) * (e - mean)).sum::<f32>() / 20000.;
+
+        assert!((mean - 3.).abs() < 0.01);
+        assert!((variance.sqrt() - 0.5).abs() < 0.025);
+    }
+
+    #[test]
+    fn accessors() {
+        let mut m = Matrix::create(3, 2, vec![1., 2., 3., 4., 5., 6.]).unwrap();
+
+        assert_eq!((m.cols(), m.rows()), (3, 2));
+
+        m.elements_mut()[1] = 7.;
+        assert_eq!(m.elements(), &[1., 7., 3., 4., 5., 6.]);
+        assert_eq!(m.into_elements(), vec![1., 7., 3., 4., 5., 6.]);
+    }
+
     #[test]
     fn addition() {
-        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]);
-        let m2 = Matrix::create(3, 2, vec![5.2, 8., 3., 4., 0., 7.]);
-        let expected = Matrix::create(3, 2, vec![5.2, 10., 6., 5., 4., 13.]);
+        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]).unwrap();
+        let m2 = Matrix::create(3, 2, vec![5.2, 8., 3., 4., 0., 7.]).unwrap();
+        let expected = Matrix::create(3, 2, vec![5.2, 10., 6., 5., 4., 13.]).unwrap();
         let result = Matrix::addition(&m1, &m2).expect("Could not add");
 
         assert_eq!(result, expected);
@@ -282,58 +847,64 @@ mod tests {
 
     #[test]
     fn addition_mismatch_shapes() {
-        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]);
-        let m2 = Matrix::create(2, 3, vec![5.2, 8., 3., 4., 0., 7.]);
-        match Matrix::addition(&m1, &m2) {
-            Ok(_) => panic!("Should error"),
-            Err(e) => assert_eq!(e, MatrixAdditionOperationError::MatricesShapesDoNotMatch),
-        };
-    }
-
-    #[test]
-    fn addition_missing_elements() {
-        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]);
-        let m2 = Matrix::create(3, 2, vec![5.2, 8., 3., 4., 0.]);
+        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]).unwrap();
+        let m2 = Matrix::create(2, 3, vec![5.2, 8., 3., 4., 0., 7.]).unwrap();
         match Matrix::addition(&m1, &m2) {
             Ok(_) => panic!("Should error"),
-            Err(e) => assert_eq!(e, MatrixAdditionOperationError::MatricesHaveDifferentNumberOfElements),
+            Err(e) => assert_eq!(e, MatrixError::ShapesDoNotMatch { operation: "addition", left: (3, 2), right: (2, 3) }),
         };
     }
 
     #[test]
     fn subtraction() {
-        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 4., 4., 7.]);
-        let m2 = Matrix::create(3, 2, vec![5.2, 8., 3., 1., 0., 6.]);
-        let expected = Matrix::create(3, 2, vec![-5.2, -6., 0., 3., 4., 1.]);
+        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 4., 4., 7.]).unwrap();
+        let m2 = Matrix::create(3, 2, vec![5.2, 8., 3., 1., 0., 6.]).unwrap();
+        let expected = Matrix::create(3, 2, vec![-5.2, -6., 0., 3., 4., 1.]).unwrap();
         let result = Matrix::subtraction(&m1, &m2).expect("Could not add");
 
         assert_eq!(result, expected);
     }
 
     #[test]
-    fn subtraction_mismatch_shapes() {
-        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]);
-        let m2 = Matrix::create(2, 3, vec![5.2, 8., 3., 4., 0., 7.]);
-        match Matrix::subtraction(&m1, &m2) {
-            Ok(_) => panic!("Should error"),
-            Err(e) => assert_eq!(e, MatrixAdditionOperationError::MatricesShapesDoNotMatch),
-        };
+    fn in_place() {
+        let mut m = Matrix::create(3, 2, vec![0., 2., 3., 4., 4., 7.]).unwrap();
+        let other = Matrix::create(3, 2, vec![1., 0.5, -1., 2., 0., 1.]).unwrap();
+        let capacity = m.elements.capacity();
+
+        m.add_in_place(&other).expect("Could not add");
+        assert_eq!(m, Matrix::addition(&Matrix::create(3, 2, vec![0., 2., 3., 4., 4., 7.]).unwrap(), &other).unwrap());
+
+        m.subtract_in_place(&other).expect("Could not subtract");
+        assert_eq!(m, Matrix::create(3, 2, vec![0., 2., 3., 4., 4., 7.]).unwrap());
+
+        m.add_scaled_in_place(&other, 2.).expect("Could not add");
+        assert_eq!(m, Matrix::create(3, 2, vec![2., 3., 1., 8., 4., 9.]).unwrap());
+
+        m.hadamard_in_place(&other).expect("Could not multiply");
+        assert_eq!(m, Matrix::create(3, 2, vec![2., 1.5, -1., 16., 0., 9.]).unwrap());
+
+        m.scale_in_place(0.5);
+        m.map_in_place(|v| v + 1.);
+        assert_eq!(m, Matrix::create(3, 2, vec![2., 1.75, 0.5, 9., 1., 5.5]).unwrap());
+        assert_eq!(m.elements.capacity(), capacity);
+
+        assert_eq!(m.add_in_place(&Matrix::create(2, 3, vec![0.; 6]).unwrap()), Err(MatrixError::ShapesDoNotMatch { operation: "add_in_place", left: (3, 2), right: (2, 3) }));
     }
 
     #[test]
-    fn subtraction_missing_elements() {
-        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]);
-        let m2 = Matrix::create(3, 2, vec![5.2, 8., 3., 4., 0.]);
+    fn subtraction_mismatch_shapes() {
+        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]).unwrap();
+        let m2 = Matrix::create(2, 3, vec![5.2, 8., 3., 4., 0., 7.]).unwrap();
         match Matrix::subtraction(&m1, &m2) {
             Ok(_) => panic!("Should error"),
-            Err(e) => assert_eq!(e, MatrixAdditionOperationError::MatricesHaveDifferentNumberOfElements),
+            Err(e) => assert_eq!(e, MatrixError::ShapesDoNotMatch { operation: "subtraction", left: (3, 2), right: (2, 3) }),
         };
     }
 
     #[test]
     fn scalar_multiplication() {
-        let m = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]);
-        let expected = Matrix::create(3, 2, vec![0., 4., 6., 2., 8., 12.]);
+        let m = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]).unwrap();
+        let expected = Matrix::create(3, 2, vec![0., 4., 6., 2., 8., 12.]).unwrap();
         let result = Matrix::scalar_multiplication(&m, 2.);
 
         assert_eq!(result, expected);
@@ -341,8 +912,8 @@ mod tests {
 
     #[test]
     fn transposition() {
-        let m = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]);
-        let expected = Matrix::create(2, 3, vec![0., 1., 2., 4., 3., 6.]);
+        let m = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]).unwrap();
+        let expected = Matrix::create(2, 3, vec![0., 1., 2., 4., 3., 6.]).unwrap();
         let result = Matrix::transposition(&m);
 
         assert_eq!(result, expected);
@@ -350,9 +921,9 @@ mod tests {
 
     #[test]
     fn matrix_multiplication() {
-        let m1 = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]);
-        let m2 = Matrix::create(2, 3, vec![0., 1000., 1., 100., 0., 10.]);
-        let expected = Matrix::create(2, 2, vec![3., 2340., 0., 1000.]);
+        let m1 = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]).unwrap();
+        let m2 = Matrix::create(2, 3, vec![0., 1000., 1., 100., 0., 10.]).unwrap();
+        let expected = Matrix::create(2, 2, vec![3., 2340., 0., 1000.]).unwrap();
         let result = Matrix::matrix_multiplication(&m1, &m2).expect("Could not multiply");
 
         assert_eq!(result, expected);
@@ -360,29 +931,81 @@ mod tests {
 
     #[test]
     fn matrix_multiplication_2() {
-        let m1 = Matrix::create(3, 1, vec![0.54, -0.64, 1.00]);
-        let m2 = Matrix::create(1, 3, vec![0.68, 0.08, 0.38]);
-        let expected = Matrix::create(1, 1, vec![0.696]);
+        let m1 = Matrix::create(3, 1, vec![0.54, -0.64, 1.00]).unwrap();
+        let m2 = Matrix::create(1, 3, vec![0.68, 0.08, 0.38]).unwrap();
+        let expected = Matrix::create(1, 1, vec![0.696]).unwrap();
         let result = Matrix::matrix_multiplication(&m1, &m2).expect("Could not multiply");
 
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn matrix_multiplication_blocked() {
+        // Big enough to cover several blocks in every direction, including partial ones
+        let (rows, inner, cols) = (130, 70, 150);
+        let m1 = Matrix::create(inner, rows, (0..(rows * inner)).map(|v| ((v % 17) as f32) - 8.).collect()).unwrap();
+        let m2 = Matrix::create(cols, inner, (0..(inner * cols)).map(|v| ((v % 13) as f32) * 0.5).collect()).unwrap();
+        let result = Matrix::matrix_multiplication(&m1, &m2).expect("Could not multiply");
+
+        for j in 0..rows {
+            for i in 0..cols {
+                let mut sum = 0.;
+
+                for k in 0..inner {
+                    sum += m1.get(k, j) * m2.get(i, k);
+                }
+
+                assert_eq!(result.get(i, j), sum);
+            }
+        }
+    }
+
+    #[test]
+    fn matrix_multiplication_into() {
+        let m1 = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]).unwrap();
+        let m2 = Matrix::create(2, 3, vec![0., 1000., 1., 100., 0., 10.]).unwrap();
+        let mut result = Matrix::create(5, 5, vec![7.; 25]).unwrap();
+        let capacity = result.elements.capacity();
+        let expected = Matrix::create(2, 2, vec![3., 2340., 0., 1000.]).unwrap();
+
+        Matrix::matrix_multiplication_into(&m1, &m2, &mut result).expect("Could not multiply");
+
+        assert_eq!(result, expected);
+        assert_eq!(result.elements.capacity(), capacity);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn parallel_matches_serial() {
+        let m1 = Matrix::create(300, 200, (0..60000).map(|i| ((i * 7) % 13) as f32 - 6.).collect::<Vec<f32>>()).unwrap();
+        let m2 = Matrix::create(150, 300, (0..45000).map(|i| ((i * 5) % 11) as f32 * 0.25).collect::<Vec<f32>>()).unwrap();
+        let run = |threads: usize| {
+            crate::network::set_threads(threads);
+            let product = Matrix::matrix_multiplication(&m1, &m2).expect("Could not multiply");
+            let sum = Matrix::addition(&product, &product).expect("Could not add");
+            crate::network::set_threads(0);
+
+            return (product, sum);
+        };
+
+        assert_eq!(run(1), run(4));
+    }
+
     #[test]
     fn matrix_multiplication_shape_mismatch() {
-        let m1 = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]);
-        let m2 = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]);
+        let m1 = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]).unwrap();
+        let m2 = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]).unwrap();
         match Matrix::matrix_multiplication(&m1, &m2) {
             Ok(_) => panic!("Should error"),
-            Err(e) => assert_eq!(e, MatrixMultiplicationOperationError::LeftMatrixColumnsDoNotEqualRightMatrixRows),
+            Err(e) => assert_eq!(e, MatrixError::ShapesDoNotMatch { operation: "matrix_multiplication", left: (3, 2), right: (3, 2) }),
         };
     }
 
     #[test]
     fn extend_columns() {
-        let m = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]);
+        let m = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]).unwrap();
         let values = vec![9., 8., 7.];
-        let expected = Matrix::create(3, 3, vec![2., 3., 4., 1., 0., 0., 9., 8., 7.]);
+        let expected = Matrix::create(3, 3, vec![2., 3., 4., 1., 0., 0., 9., 8., 7.]).unwrap();
         let result = Matrix::extend_columns(&m, values).expect("Could not extend");
 
         assert_eq!(result, expected);
@@ -390,19 +1013,19 @@ mod tests {
 
     #[test]
     fn extend_columns_not_enough_values() {
-        let m1 = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]);
+        let m1 = Matrix::create(3, 2, vec![2., 3., 4., 1., 0., 0.]).unwrap();
         let values = vec![9., 8.];
         match Matrix::extend_columns(&m1, values) {
             Ok(_) => panic!("Should error"),
-            Err(e) => assert_eq!(e, MatrixExtendOperationError::NotEnoughValuesToExtend),
+            Err(e) => assert_eq!(e, MatrixError::NotEnoughValuesToExtend { operation: "extend_columns", needed: 3, given: 2 }),
         };
     }
 
     #[test]
     fn extend_rows() {
-        let m = Matrix::create(2, 3, vec![2., 3., 4., 1., 0., 0.]);
+        let m = Matrix::create(2, 3, vec![2., 3., 4., 1., 0., 0.]).unwrap();
         let values = vec![9., 8., 7.];
-        let expected = Matrix::create(3, 3, vec![2., 3., 9., 4., 1., 8., 0., 0., 7.]);
+        let expected = Matrix::create(3, 3, vec![2., 3., 9., 4., 1., 8., 0., 0., 7.]).unwrap();
         let result = Matrix::extend_rows(&m, values).expect("Could not extend");
 
         assert_eq!(result, expected);
@@ -410,19 +1033,28 @@ mod tests {
 
     #[test]
     fn extend_rows_not_enough_values() {
-        let m1 = Matrix::create(2, 3, vec![2., 3., 4., 1., 0., 0.]);
+        let m1 = Matrix::create(2, 3, vec![2., 3., 4., 1., 0., 0.]).unwrap();
         let values = vec![9., 8.];
         match Matrix::extend_rows(&m1, values) {
             Ok(_) => panic!("Should error"),
-            Err(e) => assert_eq!(e, MatrixExtendOperationError::NotEnoughValuesToExtend),
+            Err(e) => assert_eq!(e, MatrixError::NotEnoughValuesToExtend { operation: "extend_rows", needed: 3, given: 2 }),
         };
     }
 
+    #[test]
+    fn extend_rows_empty() {
+        let no_rows: Matrix = Matrix::create(2, 0, vec![]).unwrap();
+        assert_eq!(Matrix::extend_rows(&no_rows, vec![]), Ok(Matrix::create(3, 0, vec![]).unwrap()));
+
+        let no_cols: Matrix = Matrix::create(0, 3, vec![]).unwrap();
+        assert_eq!(Matrix::extend_rows(&no_cols, vec![9., 8., 7.]), Ok(Matrix::create(1, 3, vec![9., 8., 7.]).unwrap()));
+    }
+
     #[test]
     fn hadamard() {
-        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]);
-        let m2 = Matrix::create(3, 2, vec![5.2, 8., 3., 4., 0., 7.]);
-        let expected = Matrix::create(3, 2, vec![0., 16., 9., 4., 0., 42.]);
+        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]).unwrap();
+        let m2 = Matrix::create(3, 2, vec![5.2, 8., 3., 4., 0., 7.]).unwrap();
+        let expected = Matrix::create(3, 2, vec![0., 16., 9., 4., 0., 42.]).unwrap();
         let result = Matrix::hadamard(&m1, &m2).expect("Could not hadamard");
 
         assert_eq!(result, expected);
@@ -430,28 +1062,143 @@ mod tests {
 
     #[test]
     fn hadamard_mismatch_shapes() {
-        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]);
-        let m2 = Matrix::create(2, 3, vec![5.2, 8., 3., 4., 0., 7.]);
+        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]).unwrap();
+        let m2 = Matrix::create(2, 3, vec![5.2, 8., 3., 4., 0., 7.]).unwrap();
         match Matrix::hadamard(&m1, &m2) {
             Ok(_) => panic!("Should error"),
-            Err(e) => assert_eq!(e, MatrixHadamardOperationError::MatricesShapesDoNotMatch),
+            Err(e) => assert_eq!(e, MatrixError::ShapesDoNotMatch { operation: "hadamard", left: (3, 2), right: (2, 3) }),
         };
     }
 
     #[test]
-    fn hadamard_missing_elements() {
-        let m1 = Matrix::create(3, 2, vec![0., 2., 3., 1., 4., 6.]);
-        let m2 = Matrix::create(3, 2, vec![5.2, 8., 3., 4., 0.]);
-        match Matrix::hadamard(&m1, &m2) {
-            Ok(_) => panic!("Should error"),
-            Err(e) => assert_eq!(e, MatrixHadamardOperationError::MatricesHaveDifferentNumberOfElements),
-        };
+    fn broadcasting() {
+        let m = Matrix::create(3, 2, vec![1., 2., 3., 4., 5., 6.]).unwrap();
+        let row = Matrix::create(3, 1, vec![1., 2., 4.]).unwrap();
+        let col = Matrix::create(1, 2, vec![1., -1.]).unwrap();
+        let scalar = Matrix::create(1, 1, vec![2.]).unwrap();
+
+        assert_eq!(Matrix::addition(&m, &row), Ok(Matrix::create(3, 2, vec![2., 4., 7., 5., 7., 10.]).unwrap()));
+        assert_eq!(Matrix::addition(&row, &m), Matrix::addition(&m, &row));
+        assert_eq!(Matrix::subtraction(&m, &col), Ok(Matrix::create(3, 2, vec![0., 1., 2., 5., 6., 7.]).unwrap()));
+        assert_eq!(Matrix::hadamard(&m, &scalar), Ok(Matrix::create(3, 2, vec![2., 4., 6., 8., 10., 12.]).unwrap()));
+        assert_eq!(Matrix::division(&m, &row), Ok(Matrix::create(3, 2, vec![1., 1., 0.75, 4., 2.5, 1.5]).unwrap()));
+        assert_eq!(Matrix::division(&scalar, &row), Ok(Matrix::create(3, 1, vec![2., 1., 0.5]).unwrap()));
+
+        // A row and a column make every combination of the two
+        assert_eq!(Matrix::addition(&row, &col), Ok(Matrix::create(3, 2, vec![2., 3., 5., 0., 1., 3.]).unwrap()));
+    }
+
+    #[test]
+    fn broadcasting_incompatible_shapes() {
+        let m = Matrix::create(3, 2, vec![1., 2., 3., 4., 5., 6.]).unwrap();
+        let row = Matrix::create(2, 1, vec![1., 2.]).unwrap();
+
+        assert_eq!(Matrix::addition(&m, &row), Err(MatrixError::ShapesDoNotMatch { operation: "addition", left: (3, 2), right: (2, 1) }));
+        assert_eq!(Matrix::division(&m, &row), Err(MatrixError::ShapesDoNotMatch { operation: "division", left: (3, 2), right: (2, 1) }));
+    }
+
+    #[test]
+    fn broadcasting_in_place() {
+        let mut m = Matrix::create(2, 2, vec![1., 2., 3., 4.]).unwrap();
+        let row = Matrix::create(2, 1, vec![1., 2.]).unwrap();
+
+        m.add_in_place(&row).unwrap();
+        assert_eq!(m, Matrix::create(2, 2, vec![2., 4., 4., 6.]).unwrap());
+
+        m.hadamard_in_place(&Matrix::create(1, 2, vec![2., 0.5]).unwrap()).unwrap();
+        assert_eq!(m, Matrix::create(2, 2, vec![4., 8., 2., 3.]).unwrap());
+
+        m.divide_in_place(&row).unwrap();
+        assert_eq!(m, Matrix::create(2, 2, vec![4., 4., 2., 1.5]).unwrap());
+
+        // The result has to fit in self
+        let mut small = row.clone();
+        assert_eq!(small.add_in_place(&m), Err(MatrixError::ShapesDoNotMatch { operation: "add_in_place", left: (2, 1), right: (2, 2) }));
+        assert_eq!(small, row);
     }
 
     #[test]
     fn sum() {
-        let m = Matrix::create(3, 2, vec![0., 2., 5., 8., 1.]);
+        let m = Matrix::create(3, 2, vec![0., 2., 5., 8., 1., 0.]).unwrap();
 
         assert_eq!(m.sum(), 16.);
     }
+
+    #[test]
+    fn sum_rows_and_cols() {
+        let m = Matrix::create(3, 2, vec![1., 2., 3., 4., 5., 6.]).unwrap();
+
+        assert_eq!(m.sum_rows(), Matrix::create(3, 1, vec![5., 7., 9.]).unwrap());
+        assert_eq!(m.sum_cols(), Matrix::create(1, 2, vec![6., 15.]).unwrap());
+    }
+
+    #[test]
+    fn mean_and_variance() {
+        let m: Matrix = Matrix::create(3, 2, vec![1., 2., 3., 5., 8., 3.]).unwrap();
+
+        assert_eq!(m.mean(Axis::Rows), Matrix::create(3, 1, vec![3., 5., 3.]).unwrap());
+        assert_eq!(m.mean(Axis::Cols), Matrix::create(1, 2, vec![2., 16. / 3.]).unwrap());
+        assert_eq!(m.variance(Axis::Rows), Matrix::create(3, 1, vec![4., 9., 0.]).unwrap());
+
+        let variance = m.variance(Axis::Cols);
+        assert!((variance.get(0, 0) - 2. / 3.).abs() < 1e-6);
+        assert!((variance.get(0, 1) - 38. / 9.).abs() < 1e-6);
+
+        // Large offsets would cancel out with the sum of squares formula
+        let offset: Matrix = Matrix::create(3, 1, vec![1e6 + 1., 1e6 + 2., 1e6 + 3.]).unwrap();
+        assert!((offset.variance(Axis::Cols).get(0, 0) - 2. / 3.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn max_and_min() {
+        let m = Matrix::create(3, 2, vec![1., -2., 3., -4., 5., 0.]).unwrap();
+
+        assert_eq!(m.max(Axis::Rows), Matrix::create(3, 1, vec![1., 5., 3.]).unwrap());
+        assert_eq!(m.max(Axis::Cols), Matrix::create(1, 2, vec![3., 5.]).unwrap());
+        assert_eq!(m.min(Axis::Rows), Matrix::create(3, 1, vec![-4., -2., 0.]).unwrap());
+        assert_eq!(m.min(Axis::Cols), Matrix::create(1, 2, vec![-2., -4.]).unwrap());
+    }
+
+    #[test]
+    fn argmax_and_argmin() {
+        let m = Matrix::create(3, 2, vec![1., 7., 7., -4., 5., -4.]).unwrap();
+
+        assert_eq!(m.argmax(Axis::Rows), vec![0, 0, 0]);
+        assert_eq!(m.argmax(Axis::Cols), vec![1, 1]);
+        assert_eq!(m.argmin(Axis::Rows), vec![1, 1, 1]);
+        assert_eq!(m.argmin(Axis::Cols), vec![0, 0]);
+    }
+
+    #[test]
+    fn norms() {
+        let v = Matrix::create(2, 1, vec![3., -4.]).unwrap();
+
+        assert_eq!(v.norm(Norm::L1), 7.);
+        assert_eq!(v.norm(Norm::L2), 5.);
+        assert_eq!(v.norm(Norm::Frobenius), 5.);
+        assert_eq!(Matrix::transposition(&v).norm(Norm::L2), 5.);
+
+        // The singular values are 3 and 2
+        let m: Matrix = Matrix::create(2, 2, vec![3., 0., 0., -2.]).unwrap();
+
+        assert_eq!(m.norm(Norm::L1), 5.);
+        assert!((m.norm(Norm::L2) - 3.).abs() < 1e-5);
+        assert!((m.norm(Norm::Frobenius) - 13_f32.sqrt()).abs() < 1e-6);
+
+        let r: Matrix = Matrix::create(2, 2, vec![1., 1., 0., 1.]).unwrap();
+        assert!((r.norm(Norm::L2) - (1. + 5_f32.sqrt()) / 2.).abs() < 1e-5);
+
+        // The longest row is orthogonal to the top singular vector, whose singular value is 1.9 * sqrt(3)
+        let o: Matrix = Matrix::create(2, 4, vec![2., 0., 0., 1.9, 0., 1.9, 0., 1.9]).unwrap();
+        assert!((o.norm(Norm::L2) - 1.9 * 3_f32.sqrt()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn dot() {
+        let m1 = Matrix::create(3, 1, vec![1., 2., 3.]).unwrap();
+        let m2 = Matrix::create(3, 1, vec![4., -5., 6.]).unwrap();
+
+        assert_eq!(Matrix::dot(&m1, &m2), Ok(12.));
+        assert_eq!(Matrix::dot(&m1, &Matrix::transposition(&m2)), Err(MatrixError::ShapesDoNotMatch { operation: "dot", left: (3, 1), right: (1, 3) }));
+    }
 }
\ No newline at end of file
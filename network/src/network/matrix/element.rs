@@ -0,0 +1,262 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use crate::network::matrix::scalar;
+#[cfg(feature = "simd")]
+use crate::network::matrix::simd;
+
+// A number a Matrix can hold. The element-wise kernels are part of the trait so that f32 can
+// swap in the simd ones while every other type uses the scalar loops
+pub trait Element: Copy + Debug + Display + Default + PartialEq + PartialOrd + Send + Sync + 'static
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self>
+    + AddAssign + SubAssign + MulAssign + DivAssign {
+
+    fn from_f32(v: f32) -> Self;
+
+    fn to_f32(self) -> f32;
+
+    fn from_f64(v: f64) -> Self;
+
+    fn to_f64(self) -> f64;
+
+    fn zero() -> Self {
+        return Self::from_f32(0.);
+    }
+
+    fn one() -> Self {
+        return Self::from_f32(1.);
+    }
+
+    // The smallest positive normal value, anything much below it rounds away to zero
+    fn min_positive() -> Self;
+
+    fn exp(self) -> Self;
+
+    fn ln(self) -> Self;
+
+    fn ln_1p(self) -> Self;
+
+    fn sqrt(self) -> Self;
+
+    fn tanh(self) -> Self;
+
+    fn abs(self) -> Self;
+
+    fn signum(self) -> Self;
+
+    fn powi(self, n: i32) -> Self;
+
+    fn max(self, other: Self) -> Self;
+
+    fn min(self, other: Self) -> Self;
+
+    fn addition(a: &[Self], b: &[Self], out: &mut [Self]) -> () {
+        scalar::addition(a, b, out);
+    }
+
+    fn hadamard(a: &[Self], b: &[Self], out: &mut [Self]) -> () {
+        scalar::hadamard(a, b, out);
+    }
+
+    fn scale(a: &[Self], s: Self, out: &mut [Self]) -> () {
+        scalar::scale(a, s, out);
+    }
+
+    fn add_assign(out: &mut [Self], b: &[Self]) -> () {
+        scalar::add_assign(out, b);
+    }
+
+    fn add_scaled_assign(out: &mut [Self], b: &[Self], s: Self) -> () {
+        scalar::add_scaled_assign(out, b, s);
+    }
+
+    fn hadamard_assign(out: &mut [Self], b: &[Self]) -> () {
+        scalar::hadamard_assign(out, b);
+    }
+
+    fn scale_assign(out: &mut [Self], s: Self) -> () {
+        scalar::scale_assign(out, s);
+    }
+
+    fn map<F>(a: &[Self], mapper: F) -> Vec<Self>
+    where
+        F: Fn(&Self) -> Self {
+
+        return scalar::map(a, mapper);
+    }
+
+    fn sum(a: &[Self]) -> Self {
+        return scalar::sum(a);
+    }
+}
+
+impl Element for f32 {
+    fn from_f32(v: f32) -> f32 {
+        return v;
+    }
+
+    fn to_f32(self) -> f32 {
+        return self;
+    }
+
+    fn from_f64(v: f64) -> f32 {
+        return v as f32;
+    }
+
+    fn to_f64(self) -> f64 {
+        return self as f64;
+    }
+
+    fn exp(self) -> f32 {
+        return f32::exp(self);
+    }
+
+    fn ln(self) -> f32 {
+        return f32::ln(self);
+    }
+
+    fn ln_1p(self) -> f32 {
+        return f32::ln_1p(self);
+    }
+
+    fn sqrt(self) -> f32 {
+        return f32::sqrt(self);
+    }
+
+    fn tanh(self) -> f32 {
+        return f32::tanh(self);
+    }
+
+    fn abs(self) -> f32 {
+        return f32::abs(self);
+    }
+
+    fn signum(self) -> f32 {
+        return f32::signum(self);
+    }
+
+    fn powi(self, n: i32) -> f32 {
+        return f32::powi(self, n);
+    }
+
+    fn min_positive() -> f32 {
+        return f32::MIN_POSITIVE;
+    }
+
+    fn max(self, other: f32) -> f32 {
+        return f32::max(self, other);
+    }
+
+    fn min(self, other: f32) -> f32 {
+        return f32::min(self, other);
+    }
+
+    #[cfg(feature = "simd")]
+    fn addition(a: &[f32], b: &[f32], out: &mut [f32]) -> () {
+        simd::addition(a, b, out);
+    }
+
+    #[cfg(feature = "simd")]
+    fn hadamard(a: &[f32], b: &[f32], out: &mut [f32]) -> () {
+        simd::hadamard(a, b, out);
+    }
+
+    #[cfg(feature = "simd")]
+    fn scale(a: &[f32], s: f32, out: &mut [f32]) -> () {
+        simd::scale(a, s, out);
+    }
+
+    #[cfg(feature = "simd")]
+    fn add_assign(out: &mut [f32], b: &[f32]) -> () {
+        simd::add_assign(out, b);
+    }
+
+    #[cfg(feature = "simd")]
+    fn add_scaled_assign(out: &mut [f32], b: &[f32], s: f32) -> () {
+        simd::add_scaled_assign(out, b, s);
+    }
+
+    #[cfg(feature = "simd")]
+    fn hadamard_assign(out: &mut [f32], b: &[f32]) -> () {
+        simd::hadamard_assign(out, b);
+    }
+
+    #[cfg(feature = "simd")]
+    fn scale_assign(out: &mut [f32], s: f32) -> () {
+        simd::scale_assign(out, s);
+    }
+
+    #[cfg(feature = "simd")]
+    fn map<F>(a: &[f32], mapper: F) -> Vec<f32>
+    where
+        F: Fn(&f32) -> f32 {
+
+        return simd::map(a, mapper);
+    }
+
+    #[cfg(feature = "simd")]
+    fn sum(a: &[f32]) -> f32 {
+        return simd::sum(a);
+    }
+}
+
+impl Element for f64 {
+    fn from_f32(v: f32) -> f64 {
+        return v as f64;
+    }
+
+    fn to_f32(self) -> f32 {
+        return self as f32;
+    }
+
+    fn from_f64(v: f64) -> f64 {
+        return v;
+    }
+
+    fn to_f64(self) -> f64 {
+        return self;
+    }
+
+    fn exp(self) -> f64 {
+        return f64::exp(self);
+    }
+
+    fn ln(self) -> f64 {
+        return f64::ln(self);
+    }
+
+    fn ln_1p(self) -> f64 {
+        return f64::ln_1p(self);
+    }
+
+    fn sqrt(self) -> f64 {
+        return f64::sqrt(self);
+    }
+
+    fn tanh(self) -> f64 {
+        return f64::tanh(self);
+    }
+
+    fn abs(self) -> f64 {
+        return f64::abs(self);
+    }
+
+    fn signum(self) -> f64 {
+        return f64::signum(self);
+    }
+
+    fn powi(self, n: i32) -> f64 {
+        return f64::powi(self, n);
+    }
+
+    fn min_positive() -> f64 {
+        return f64::MIN_POSITIVE;
+    }
+
+    fn max(self, other: f64) -> f64 {
+        return f64::max(self, other);
+    }
+
+    fn min(self, other: f64) -> f64 {
+        return f64::min(self, other);
+    }
+}
@@ -0,0 +1,67 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+// Shapes are (cols, rows), the same order Matrix::create takes them in
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatrixError {
+    // The operation can't combine matrices of these shapes
+    ShapesDoNotMatch {
+        operation: &'static str,
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    // A matrix doesn't have cols * rows elements
+    WrongNumberOfElements {
+        operation: &'static str,
+        shape: (usize, usize),
+        elements: usize,
+    },
+    NotEnoughValuesToExtend {
+        operation: &'static str,
+        needed: usize,
+        given: usize,
+    },
+}
+
+impl MatrixError {
+    pub fn operation(&self) -> &'static str {
+        return match self {
+            MatrixError::ShapesDoNotMatch { operation, .. } => operation,
+            MatrixError::WrongNumberOfElements { operation, .. } => operation,
+            MatrixError::NotEnoughValuesToExtend { operation, .. } => operation,
+        };
+    }
+}
+
+impl Display for MatrixError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            MatrixError::ShapesDoNotMatch { operation, left, right } => {
+                write!(f, "{} can't combine a matrix of {} cols x {} rows with one of {} cols x {} rows", operation, left.0, left.1, right.0, right.1)
+            },
+            MatrixError::WrongNumberOfElements { operation, shape, elements } => {
+                write!(f, "{} was given a matrix of {} cols x {} rows holding {} elements instead of {}", operation, shape.0, shape.1, elements, shape.0 * shape.1)
+            },
+            MatrixError::NotEnoughValuesToExtend { operation, needed, given } => {
+                write!(f, "{} needs {} values but was given {}", operation, needed, given)
+            },
+        };
+    }
+}
+
+impl Error for MatrixError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::matrix::MatrixError;
+
+    #[test]
+    fn display() {
+        let e = MatrixError::ShapesDoNotMatch { operation: "addition", left: (3, 2), right: (2, 3) };
+
+        assert_eq!(e.to_string(), "addition can't combine a matrix of 3 cols x 2 rows with one of 2 cols x 3 rows");
+        assert_eq!(e.operation(), "addition");
+        assert_eq!(MatrixError::WrongNumberOfElements { operation: "hadamard", shape: (3, 2), elements: 5 }.to_string(), "hadamard was given a matrix of 3 cols x 2 rows holding 5 elements instead of 6");
+        assert_eq!(MatrixError::NotEnoughValuesToExtend { operation: "extend_rows", needed: 2, given: 1 }.to_string(), "extend_rows needs 2 values but was given 1");
+    }
+}
@@ -0,0 +1,304 @@
+// 16 bit floats for storage. Arithmetic converts to f32, works there and rounds the result back,
+// so they save memory rather than time
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use crate::network::matrix::Element;
+
+// IEEE 754 half precision: 5 exponent bits and 10 mantissa bits
+#[derive(Debug, Clone, Copy, Default)]
+pub struct F16(u16);
+
+// bfloat16: the top half of an f32, keeping its range but only 7 mantissa bits
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bf16(u16);
+
+impl F16 {
+    pub fn from_bits(bits: u16) -> F16 {
+        return F16(bits);
+    }
+
+    pub fn to_bits(self) -> u16 {
+        return self.0;
+    }
+}
+
+impl Bf16 {
+    pub fn from_bits(bits: u16) -> Bf16 {
+        return Bf16(bits);
+    }
+
+    pub fn to_bits(self) -> u16 {
+        return self.0;
+    }
+}
+
+// Rounds to the nearest half, ties to even
+fn f32_to_f16(v: f32) -> u16 {
+    let bits = v.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        // Infinity stays infinity and NaN stays NaN
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+
+    let half_exponent = exponent - 127 + 15;
+
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    let (value, shift) = if half_exponent <= 0 {
+        // Too small for a normal half so it becomes subnormal, or zero when even that underflows
+        if half_exponent < -10 {
+            return sign;
+        }
+
+        (mantissa | 0x80_0000, (14 - half_exponent) as u32)
+    } else {
+        (((half_exponent as u32) << 23) | mantissa, 13)
+    };
+
+    let truncated = value >> shift;
+    let remainder = value & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    let round_up = remainder > halfway || (remainder == halfway && (truncated & 1) == 1);
+
+    // Rounding up out of the mantissa carries into the exponent, which is the right answer
+    return sign | (truncated + round_up as u32) as u16;
+}
+
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits & 0x8000) as u32) << 16;
+    let exponent = ((bits >> 10) & 0x1f) as u32;
+    let mantissa = (bits & 0x3ff) as u32;
+
+    return match exponent {
+        0 => {
+            let v = mantissa as f32 / 16_777_216.;
+
+            if sign == 0 { v } else { -v }
+        },
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    };
+}
+
+// Rounds to the nearest bfloat16, ties to even
+fn f32_to_bf16(v: f32) -> u16 {
+    let bits = v.to_bits();
+
+    if v.is_nan() {
+        return ((bits >> 16) | 0x40) as u16;
+    }
+
+    return ((bits + 0x7fff + ((bits >> 16) & 1)) >> 16) as u16;
+}
+
+fn bf16_to_f32(bits: u16) -> f32 {
+    return f32::from_bits((bits as u32) << 16);
+}
+
+// Both types only differ in how they convert to and from f32
+macro_rules! half {
+    ($name:ident, $encode:ident, $decode:ident, $min_positive:expr) => {
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                return $decode(self.0) == $decode(other.0);
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                return $decode(self.0).partial_cmp(&$decode(other.0));
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                return Display::fmt(&$decode(self.0), f);
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+
+            fn add(self, other: $name) -> $name {
+                return $name($encode($decode(self.0) + $decode(other.0)));
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+
+            fn sub(self, other: $name) -> $name {
+                return $name($encode($decode(self.0) - $decode(other.0)));
+            }
+        }
+
+        impl Mul for $name {
+            type Output = $name;
+
+            fn mul(self, other: $name) -> $name {
+                return $name($encode($decode(self.0) * $decode(other.0)));
+            }
+        }
+
+        impl Div for $name {
+            type Output = $name;
+
+            fn div(self, other: $name) -> $name {
+                return $name($encode($decode(self.0) / $decode(other.0)));
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+
+            fn neg(self) -> $name {
+                return $name($encode(-$decode(self.0)));
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: $name) -> () {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: $name) -> () {
+                *self = *self - other;
+            }
+        }
+
+        impl MulAssign for $name {
+            fn mul_assign(&mut self, other: $name) -> () {
+                *self = *self * other;
+            }
+        }
+
+        impl DivAssign for $name {
+            fn div_assign(&mut self, other: $name) -> () {
+                *self = *self / other;
+            }
+        }
+
+        impl Element for $name {
+            fn from_f32(v: f32) -> $name {
+                return $name($encode(v));
+            }
+
+            fn to_f32(self) -> f32 {
+                return $decode(self.0);
+            }
+
+            // Goes through f32 so can round twice, which is only ever out by the last bit
+            fn from_f64(v: f64) -> $name {
+                return $name($encode(v as f32));
+            }
+
+            fn to_f64(self) -> f64 {
+                return $decode(self.0) as f64;
+            }
+
+            fn exp(self) -> $name {
+                return $name($encode($decode(self.0).exp()));
+            }
+
+            fn ln(self) -> $name {
+                return $name($encode($decode(self.0).ln()));
+            }
+
+            fn ln_1p(self) -> $name {
+                return $name($encode($decode(self.0).ln_1p()));
+            }
+
+            fn sqrt(self) -> $name {
+                return $name($encode($decode(self.0).sqrt()));
+            }
+
+            fn tanh(self) -> $name {
+                return $name($encode($decode(self.0).tanh()));
+            }
+
+            fn abs(self) -> $name {
+                return $name($encode($decode(self.0).abs()));
+            }
+
+            fn signum(self) -> $name {
+                return $name($encode($decode(self.0).signum()));
+            }
+
+            fn powi(self, n: i32) -> $name {
+                return $name($encode($decode(self.0).powi(n)));
+            }
+
+            fn min_positive() -> $name {
+                return $name($encode($min_positive));
+            }
+
+            fn max(self, other: $name) -> $name {
+                return $name($encode($decode(self.0).max($decode(other.0))));
+            }
+
+            fn min(self, other: $name) -> $name {
+                return $name($encode($decode(self.0).min($decode(other.0))));
+            }
+        }
+    };
+}
+
+// bf16 has the exponent of an f32 so the same smallest normal, where f16 stops at 2^-14
+half!(F16, f32_to_f16, f16_to_f32, 0.000061035156);
+half!(Bf16, f32_to_bf16, bf16_to_f32, f32::MIN_POSITIVE);
+
+#[cfg(test)]
+mod tests {
+    use crate::network::matrix::{Bf16, Element, F16};
+
+    #[test]
+    fn f16_conversions() {
+        assert_eq!(F16::from_f32(1.).to_bits(), 0x3c00);
+        assert_eq!(F16::from_f32(-2.).to_bits(), 0xc000);
+        assert_eq!(F16::from_f32(65504.).to_bits(), 0x7bff);
+        assert_eq!(F16::from_f32(70000.).to_bits(), 0x7c00);
+        assert_eq!(F16::from_f32(0.000000059604645).to_bits(), 0x0001);
+        assert_eq!(F16::from_f32(0.00000001).to_bits(), 0x0000);
+        assert!(F16::from_f32(f32::NAN).to_f32().is_nan());
+        assert_eq!(F16::min_positive().to_bits(), 0x0400);
+
+        // 1 + 2^-11 is halfway between 1 and the next half so rounds to the even one, 1
+        assert_eq!(F16::from_f32(1. + 1. / 2048.).to_bits(), 0x3c00);
+        assert_eq!(F16::from_f32(1.001).to_bits(), 0x3c01);
+
+        for bits in [0x0001, 0x03ff, 0x0400, 0x3555, 0x7bff, 0x8400, 0xc000] {
+            assert_eq!(F16::from_f32(F16::from_bits(bits).to_f32()).to_bits(), bits);
+        }
+    }
+
+    #[test]
+    fn bf16_conversions() {
+        assert_eq!(Bf16::from_f32(1.).to_bits(), 0x3f80);
+        assert_eq!(Bf16::from_f32(-2.).to_bits(), 0xc000);
+        assert_eq!(Bf16::from_f32(1. + 1. / 256.).to_bits(), 0x3f80);
+        assert_eq!(Bf16::from_f32(1. + 3. / 256.).to_bits(), 0x3f82);
+        assert_eq!(Bf16::from_f32(3.0e38).to_bits(), 0x7f62);
+        assert!(Bf16::from_f32(f32::NAN).to_f32().is_nan());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = F16::from_f32(1.5);
+        let b = F16::from_f32(0.25);
+
+        assert_eq!((a + b).to_f32(), 1.75);
+        assert_eq!((a * b).to_f32(), 0.375);
+        assert_eq!((-a).to_f32(), -1.5);
+        assert!(a > b);
+        assert_eq!(format!("{:.2}", Bf16::from_f32(0.5)), "0.50");
+    }
+}
@@ -0,0 +1,206 @@
+// Operators for the element-wise arithmetic. They panic when the shapes can't be broadcast, so
+// use the Matrix functions directly where a mismatch is something to recover from.
+// Owned matrices on the left are reused for the result rather than allocating another, unless
+// broadcasting makes the result bigger than them
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use crate::network::matrix::{Element, Matrix};
+
+impl<T: Element> AddAssign<&Matrix<T>> for Matrix<T> {
+    fn add_assign(&mut self, m: &Matrix<T>) -> () {
+        self.add_in_place(m).expect("Matrix must broadcast to the shape of the left hand side to add");
+    }
+}
+
+impl<T: Element> AddAssign<Matrix<T>> for Matrix<T> {
+    fn add_assign(&mut self, m: Matrix<T>) -> () {
+        *self += &m;
+    }
+}
+
+impl<T: Element> SubAssign<&Matrix<T>> for Matrix<T> {
+    fn sub_assign(&mut self, m: &Matrix<T>) -> () {
+        self.subtract_in_place(m).expect("Matrix must broadcast to the shape of the left hand side to subtract");
+    }
+}
+
+impl<T: Element> SubAssign<Matrix<T>> for Matrix<T> {
+    fn sub_assign(&mut self, m: Matrix<T>) -> () {
+        *self -= &m;
+    }
+}
+
+impl<T: Element> MulAssign<T> for Matrix<T> {
+    fn mul_assign(&mut self, s: T) -> () {
+        self.scale_in_place(s);
+    }
+}
+
+impl<T: Element> Add<&Matrix<T>> for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(mut self, m: &Matrix<T>) -> Matrix<T> {
+        if self.add_in_place(m).is_err() {
+            return &self + m;
+        }
+
+        return self;
+    }
+}
+
+impl<T: Element> Add<Matrix<T>> for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, m: Matrix<T>) -> Matrix<T> {
+        return self + &m;
+    }
+}
+
+impl<T: Element> Add<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::addition(self, m).expect("Matrices must broadcast to add");
+    }
+}
+
+impl<T: Element> Add<Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, m: Matrix<T>) -> Matrix<T> {
+        // Addition commutes so the right hand side can hold the result
+        return m + self;
+    }
+}
+
+impl<T: Element> Sub<&Matrix<T>> for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(mut self, m: &Matrix<T>) -> Matrix<T> {
+        if self.subtract_in_place(m).is_err() {
+            return &self - m;
+        }
+
+        return self;
+    }
+}
+
+impl<T: Element> Sub<Matrix<T>> for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(self, m: Matrix<T>) -> Matrix<T> {
+        return self - &m;
+    }
+}
+
+impl<T: Element> Sub<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(self, m: &Matrix<T>) -> Matrix<T> {
+        return Matrix::subtraction(self, m).expect("Matrices must broadcast to subtract");
+    }
+}
+
+impl<T: Element> Sub<Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(self, mut m: Matrix<T>) -> Matrix<T> {
+        // a - b = -b + a
+        m *= -T::one();
+
+        return m + self;
+    }
+}
+
+impl<T: Element> Mul<T> for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(mut self, s: T) -> Matrix<T> {
+        self *= s;
+
+        return self;
+    }
+}
+
+impl<T: Element> Mul<T> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, s: T) -> Matrix<T> {
+        return Matrix::scalar_multiplication(self, s);
+    }
+}
+
+impl<T: Element> Neg for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn neg(self) -> Matrix<T> {
+        return self * -T::one();
+    }
+}
+
+impl<T: Element> Neg for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn neg(self) -> Matrix<T> {
+        return self * -T::one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::matrix::Matrix;
+
+    #[test]
+    fn operators() {
+        let a = Matrix::create(2, 2, vec![1., 2., 3., 4.]).unwrap();
+        let b = Matrix::create(2, 2, vec![0.5, -1., 2., 0.]).unwrap();
+
+        assert_eq!(&a + &b, Matrix::create(2, 2, vec![1.5, 1., 5., 4.]).unwrap());
+        assert_eq!(a.clone() + &b, &a + &b);
+        assert_eq!(&a + b.clone(), &a + &b);
+        assert_eq!(a.clone() + b.clone(), &a + &b);
+
+        assert_eq!(&a - &b, Matrix::create(2, 2, vec![0.5, 3., 1., 4.]).unwrap());
+        assert_eq!(a.clone() - &b, &a - &b);
+        assert_eq!(&a - b.clone(), &a - &b);
+        assert_eq!(a.clone() - b.clone(), &a - &b);
+
+        assert_eq!(&a * 2., Matrix::create(2, 2, vec![2., 4., 6., 8.]).unwrap());
+        assert_eq!(a.clone() * 2., &a * 2.);
+        assert_eq!(-&a, Matrix::create(2, 2, vec![-1., -2., -3., -4.]).unwrap());
+        assert_eq!(-a.clone(), -&a);
+    }
+
+    #[test]
+    fn assign_operators() {
+        let mut m = Matrix::create(2, 1, vec![1., 2.]).unwrap();
+        let capacity = m.elements.capacity();
+
+        m += &Matrix::create(2, 1, vec![3., 4.]).unwrap();
+        assert_eq!(m, Matrix::create(2, 1, vec![4., 6.]).unwrap());
+
+        m -= Matrix::create(2, 1, vec![1., 1.]).unwrap();
+        assert_eq!(m, Matrix::create(2, 1, vec![3., 5.]).unwrap());
+
+        m *= 0.5;
+        assert_eq!(m, Matrix::create(2, 1, vec![1.5, 2.5]).unwrap());
+        assert_eq!(m.elements.capacity(), capacity);
+    }
+
+    #[test]
+    fn broadcasting_operators() {
+        let row = Matrix::create(2, 1, vec![10., 20.]).unwrap();
+        let batch = Matrix::create(2, 2, vec![1., 2., 3., 4.]).unwrap();
+
+        assert_eq!(&batch + &row, Matrix::create(2, 2, vec![11., 22., 13., 24.]).unwrap());
+        assert_eq!(row.clone() + &batch, &batch + &row);
+        assert_eq!(&row + batch.clone(), &batch + &row);
+        assert_eq!(row.clone() - batch.clone(), Matrix::create(2, 2, vec![9., 18., 7., 16.]).unwrap());
+        assert_eq!(&row - batch.clone(), row.clone() - &batch);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mismatched_shapes_panic() {
+        let _ = &Matrix::create(2, 1, vec![1., 2.]).unwrap() + &Matrix::create(3, 1, vec![1., 2., 3.]).unwrap();
+    }
+}
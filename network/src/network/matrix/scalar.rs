@@ -0,0 +1,58 @@
+// One element at a time, the reference the simd kernels are tested against.
+// The element-wise kernels fill out, reading as many elements of the inputs as out is long.
+// The _assign kernels update out in place instead
+use crate::network::matrix::Element;
+
+pub fn addition<T: Element>(a: &[T], b: &[T], out: &mut [T]) -> () {
+    for ((o, x), y) in out.iter_mut().zip(a).zip(b) {
+        *o = *x + *y;
+    }
+}
+
+pub fn hadamard<T: Element>(a: &[T], b: &[T], out: &mut [T]) -> () {
+    for ((o, x), y) in out.iter_mut().zip(a).zip(b) {
+        *o = *x * *y;
+    }
+}
+
+pub fn scale<T: Element>(a: &[T], s: T, out: &mut [T]) -> () {
+    for (o, x) in out.iter_mut().zip(a) {
+        *o = *x * s;
+    }
+}
+
+pub fn add_assign<T: Element>(out: &mut [T], b: &[T]) -> () {
+    for (o, y) in out.iter_mut().zip(b) {
+        *o += *y;
+    }
+}
+
+pub fn add_scaled_assign<T: Element>(out: &mut [T], b: &[T], s: T) -> () {
+    for (o, y) in out.iter_mut().zip(b) {
+        *o += *y * s;
+    }
+}
+
+pub fn hadamard_assign<T: Element>(out: &mut [T], b: &[T]) -> () {
+    for (o, y) in out.iter_mut().zip(b) {
+        *o *= *y;
+    }
+}
+
+pub fn scale_assign<T: Element>(out: &mut [T], s: T) -> () {
+    for o in out.iter_mut() {
+        *o *= s;
+    }
+}
+
+pub fn map<T, F>(a: &[T], mapper: F) -> Vec<T>
+where
+    T: Element,
+    F: Fn(&T) -> T {
+
+    return a.iter().map(mapper).collect::<Vec<T>>();
+}
+
+pub fn sum<T: Element>(a: &[T]) -> T {
+    return a.iter().fold(T::zero(), |acc, e| *e + acc);
+}
@@ -0,0 +1,286 @@
+// AVX kernels picked at runtime, falling back to the scalar ones on other CPUs.
+// Like the scalar kernels the element-wise ones fill out from the start of the inputs.
+// The element-wise kernels give exactly the scalar results. sum keeps a running total
+// per lane so it adds in a different order and can differ in the last few bits
+use crate::network::matrix::scalar;
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+const LANES: usize = 8;
+
+pub fn addition(a: &[f32], b: &[f32], out: &mut [f32]) -> () {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        return unsafe { avx_addition(a, b, out) };
+    }
+
+    scalar::addition(a, b, out);
+}
+
+pub fn hadamard(a: &[f32], b: &[f32], out: &mut [f32]) -> () {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        return unsafe { avx_hadamard(a, b, out) };
+    }
+
+    scalar::hadamard(a, b, out);
+}
+
+pub fn scale(a: &[f32], s: f32, out: &mut [f32]) -> () {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        return unsafe { avx_scale(a, s, out) };
+    }
+
+    scalar::scale(a, s, out);
+}
+
+pub fn add_assign(out: &mut [f32], b: &[f32]) -> () {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        return unsafe { avx_add_scaled_assign(out, b, 1.) };
+    }
+
+    scalar::add_assign(out, b);
+}
+
+pub fn add_scaled_assign(out: &mut [f32], b: &[f32], s: f32) -> () {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        return unsafe { avx_add_scaled_assign(out, b, s) };
+    }
+
+    scalar::add_scaled_assign(out, b, s);
+}
+
+pub fn hadamard_assign(out: &mut [f32], b: &[f32]) -> () {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        return unsafe { avx_hadamard_assign(out, b) };
+    }
+
+    scalar::hadamard_assign(out, b);
+}
+
+pub fn scale_assign(out: &mut [f32], s: f32) -> () {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        return unsafe { avx_scale_assign(out, s) };
+    }
+
+    scalar::scale_assign(out, s);
+}
+
+// An arbitrary closure can't be written with intrinsics, so this hands the compiler
+// fixed width chunks it can vectorise once the closure is inlined
+pub fn map<F>(a: &[f32], mapper: F) -> Vec<f32>
+where
+    F: Fn(&f32) -> f32 {
+
+    let mut result = Vec::with_capacity(a.len());
+    let chunks = a.chunks_exact(LANES);
+    let remainder = chunks.remainder();
+
+    for chunk in chunks {
+        let mut lanes = [0.; LANES];
+
+        for (l, e) in lanes.iter_mut().zip(chunk) {
+            *l = mapper(e);
+        }
+
+        result.extend_from_slice(&lanes);
+    }
+
+    result.extend(scalar::map(remainder, &mapper));
+
+    return result;
+}
+
+pub fn sum(a: &[f32]) -> f32 {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        return unsafe { avx_sum(a) };
+    }
+
+    return scalar::sum(a);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn avx_addition(a: &[f32], b: &[f32], out: &mut [f32]) -> () {
+    let len = out.len().min(a.len()).min(b.len());
+    let end = len - (len % LANES);
+
+    for i in (0..end).step_by(LANES) {
+        let va = _mm256_loadu_ps(a.as_ptr().add(i));
+        let vb = _mm256_loadu_ps(b.as_ptr().add(i));
+        _mm256_storeu_ps(out.as_mut_ptr().add(i), _mm256_add_ps(va, vb));
+    }
+
+    scalar::addition(&a[end..len], &b[end..len], &mut out[end..len]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn avx_hadamard(a: &[f32], b: &[f32], out: &mut [f32]) -> () {
+    let len = out.len().min(a.len()).min(b.len());
+    let end = len - (len % LANES);
+
+    for i in (0..end).step_by(LANES) {
+        let va = _mm256_loadu_ps(a.as_ptr().add(i));
+        let vb = _mm256_loadu_ps(b.as_ptr().add(i));
+        _mm256_storeu_ps(out.as_mut_ptr().add(i), _mm256_mul_ps(va, vb));
+    }
+
+    scalar::hadamard(&a[end..len], &b[end..len], &mut out[end..len]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn avx_scale(a: &[f32], s: f32, out: &mut [f32]) -> () {
+    let len = out.len().min(a.len());
+    let end = len - (len % LANES);
+    let vs = _mm256_set1_ps(s);
+
+    for i in (0..end).step_by(LANES) {
+        let va = _mm256_loadu_ps(a.as_ptr().add(i));
+        _mm256_storeu_ps(out.as_mut_ptr().add(i), _mm256_mul_ps(va, vs));
+    }
+
+    scalar::scale(&a[end..len], s, &mut out[end..len]);
+}
+
+// Multiplying by 1 is exact, so add_assign can share this without changing its results
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn avx_add_scaled_assign(out: &mut [f32], b: &[f32], s: f32) -> () {
+    let len = out.len().min(b.len());
+    let end = len - (len % LANES);
+    let vs = _mm256_set1_ps(s);
+
+    for i in (0..end).step_by(LANES) {
+        let vo = _mm256_loadu_ps(out.as_ptr().add(i));
+        let vb = _mm256_loadu_ps(b.as_ptr().add(i));
+        _mm256_storeu_ps(out.as_mut_ptr().add(i), _mm256_add_ps(vo, _mm256_mul_ps(vb, vs)));
+    }
+
+    scalar::add_scaled_assign(&mut out[end..len], &b[end..len], s);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn avx_hadamard_assign(out: &mut [f32], b: &[f32]) -> () {
+    let len = out.len().min(b.len());
+    let end = len - (len % LANES);
+
+    for i in (0..end).step_by(LANES) {
+        let vo = _mm256_loadu_ps(out.as_ptr().add(i));
+        let vb = _mm256_loadu_ps(b.as_ptr().add(i));
+        _mm256_storeu_ps(out.as_mut_ptr().add(i), _mm256_mul_ps(vo, vb));
+    }
+
+    scalar::hadamard_assign(&mut out[end..len], &b[end..len]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn avx_scale_assign(out: &mut [f32], s: f32) -> () {
+    let len = out.len();
+    let end = len - (len % LANES);
+    let vs = _mm256_set1_ps(s);
+
+    for i in (0..end).step_by(LANES) {
+        let vo = _mm256_loadu_ps(out.as_ptr().add(i));
+        _mm256_storeu_ps(out.as_mut_ptr().add(i), _mm256_mul_ps(vo, vs));
+    }
+
+    scalar::scale_assign(&mut out[end..len], s);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn avx_sum(a: &[f32]) -> f32 {
+    let len = a.len();
+    let end = len - (len % LANES);
+    let mut totals = _mm256_setzero_ps();
+
+    for i in (0..end).step_by(LANES) {
+        totals = _mm256_add_ps(totals, _mm256_loadu_ps(a.as_ptr().add(i)));
+    }
+
+    let mut lanes = [0.; LANES];
+    _mm256_storeu_ps(lanes.as_mut_ptr(), totals);
+
+    let mut result = scalar::sum(&lanes);
+
+    for e in a[end..len].iter() {
+        result += e;
+    }
+
+    return result;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::matrix::{scalar, simd};
+
+    fn values(len: usize, seed: usize) -> Vec<f32> {
+        return (0..len).map(|v| ((((v + 1) * seed) % 101) as f32) / 7. - 6.).collect::<Vec<f32>>();
+    }
+
+    #[test]
+    fn element_wise_matches_scalar() {
+        // Lengths either side of a whole number of lanes
+        for len in [0, 1, 7, 8, 9, 64, 100] {
+            let a = values(len, 3);
+            let b = values(len, 11);
+
+            let mut result = vec![0.; len];
+            let mut expected = vec![0.; len];
+
+            simd::addition(&a, &b, &mut result);
+            scalar::addition(&a, &b, &mut expected);
+            assert_eq!(result, expected);
+
+            simd::hadamard(&a, &b, &mut result);
+            scalar::hadamard(&a, &b, &mut expected);
+            assert_eq!(result, expected);
+
+            simd::scale(&a, -1.7, &mut result);
+            scalar::scale(&a, -1.7, &mut expected);
+            assert_eq!(result, expected);
+
+            assert_eq!(simd::map(&a, |v| v.max(0.)), scalar::map(&a, |v| v.max(0.)));
+
+            let mut result = a.clone();
+            let mut expected = a.clone();
+
+            simd::add_assign(&mut result, &b);
+            scalar::add_assign(&mut expected, &b);
+            assert_eq!(result, expected);
+
+            simd::add_scaled_assign(&mut result, &b, 0.3);
+            scalar::add_scaled_assign(&mut expected, &b, 0.3);
+            assert_eq!(result, expected);
+
+            simd::hadamard_assign(&mut result, &b);
+            scalar::hadamard_assign(&mut expected, &b);
+            assert_eq!(result, expected);
+
+            simd::scale_assign(&mut result, -1.7);
+            scalar::scale_assign(&mut expected, -1.7);
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn sum_matches_scalar() {
+        for len in [0, 1, 7, 8, 9, 64, 1000] {
+            let a = values(len, 5);
+            let expected = scalar::sum(&a);
+
+            assert!((simd::sum(&a) - expected).abs() <= expected.abs() * 0.00001);
+        }
+    }
+}
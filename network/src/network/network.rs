@@ -1,78 +1,597 @@
-use crate::network::activation::relu::Relu;
-use crate::network::matrix::Matrix;
-use crate::network::TrainingBatch;
-use super::Layer;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use rand::{ChaChaRng, Rng, SeedableRng};
+use crate::network::activation::Activation;
+use crate::network::initialiser::{Initialiser, XavierUniform, Zeros};
+use crate::network::layer::{Layer, Saved};
+use crate::network::loss::{Loss, MeanSquaredError};
+use crate::network::matrix::{Element, Matrix, MatrixError};
+use crate::network::optimizer::{Optimizer, Sgd};
+use crate::network::parallel;
+use crate::network::serialisation::{self, SerialisationError};
+use crate::network::tensor::{Tensor, TensorError};
+use crate::network::{AvgPool2D, Conv2D, Dense, Dropout, Flatten, GlobalAvgPool, LayerNorm, MaxPool2D, TrainingBatch};
 
-pub struct Network {
-    layers: Vec<Layer>,
+const GRADIENT_CHUNK: usize = 32;
+
+pub struct Network<T: Element = f32> {
+    layers: Vec<Box<dyn Layer<T>>>,
+    loss: Box<dyn Loss<T>>,
+    optimizer: Box<dyn Optimizer<T>>,
+    seed: u32,
+    steps: u32,
 }
 
-impl Network {
-    pub fn create(network_shape: Vec<usize>, mut input_nodes: usize) -> Network {
-        return Network {
-            layers: network_shape.iter().map(|num_of_nodes| {
-                let layer = Layer::create(*num_of_nodes, input_nodes);
-                input_nodes = *num_of_nodes;
+impl<T: Element> Network<T> {
+    pub fn create(network_shape: Vec<(usize, Box<dyn Activation<T>>)>, input_nodes: usize, rng: &mut dyn Rng) -> Network<T> {
+        return Network::create_with_initialisers(network_shape, input_nodes, &XavierUniform {}, &Zeros {}, rng);
+    }
+
+    pub fn create_with_initialisers(network_shape: Vec<(usize, Box<dyn Activation<T>>)>, mut input_nodes: usize, weights: &dyn Initialiser, bias: &dyn Initialiser, rng: &mut dyn Rng) -> Network<T> {
+        return Network::from_layers(network_shape.into_iter().map(|(num_of_nodes, activation)| {
+            let layer = Dense::create_with_initialisers(num_of_nodes, input_nodes, activation, weights, bias, rng);
+            input_nodes = num_of_nodes;
+
+            return Box::new(layer) as Box<dyn Layer<T>>;
+        }).collect::<Vec<Box<dyn Layer<T>>>>());
+    }
 
-                return layer;
-            }).collect::<Vec<Layer>>(),
+    // Each input is given to the first layer as a single row, which it reshapes if it needs to
+    pub fn from_layers(layers: Vec<Box<dyn Layer<T>>>) -> Network<T> {
+        return Network {
+            layers,
+            loss: Box::new(MeanSquaredError {}),
+            optimizer: Box::new(Sgd::create(0.)),
+            seed: 0,
+            steps: 0,
         };
     }
 
-    pub fn feed_forward(&self, inputs: Vec<f32>) -> Vec<f32> {
-        let mut food = Matrix::from_vec(inputs);
+    pub fn with_loss(mut self, loss: Box<dyn Loss<T>>) -> Network<T> {
+        self.loss = loss;
+
+        return self;
+    }
+
+    pub fn with_optimizer(mut self, optimizer: Box<dyn Optimizer<T>>) -> Network<T> {
+        self.optimizer = optimizer;
+
+        return self;
+    }
+
+    // Seeds the randomness layers like dropout use while training
+    pub fn with_seed(mut self, seed: u32) -> Network<T> {
+        self.seed = seed;
+
+        return self;
+    }
+
+    pub fn layers(&self) -> &[Box<dyn Layer<T>>] {
+        return &self.layers;
+    }
+
+    pub fn feed_forward(&self, inputs: Vec<T>) -> Result<Vec<T>, NetworkError> {
+        return Ok(self.run(Tensor::from_matrix(&Matrix::from_vec(inputs)))?.to_vec());
+    }
+
+    // Runs every input through as one batch, a row per input, so each dense layer is a single multiplication
+    pub fn feed_forward_batch(&self, inputs: Vec<Vec<T>>) -> Result<Vec<Vec<T>>, NetworkError> {
+        if inputs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        return Ok(self.run(Tensor::from_matrix(&Matrix::from_rows(inputs)?))?.batch_matrix()?.to_rows());
+    }
+
+    fn run(&self, inputs: Tensor<T>) -> Result<Tensor<T>, NetworkError> {
+        let mut food = inputs;
 
         for layer in self.layers.iter() {
-            food = layer.feed_forward(food);
+            food = layer.forward(&food, None)?.0;
         }
 
-        return food.elements;
+        return Ok(food);
+    }
+
+    // The shape of one sample's output for one sample of this shape
+    pub fn output_shape(&self, input: &[usize]) -> Vec<usize> {
+        return self.layers.iter().fold(input.to_vec(), |shape, layer| layer.output_shape(&shape));
     }
 
-    pub fn train(&mut self, batch: Vec<TrainingBatch>, learning_rate: f32) -> () {
-        // Without activation the gradient for a layer is 2x(xm - t)
-        // We can omit the 2 as we don't need the exact amount just a general direction
-        // g = x(xm - t)
+    // The largest im2col matrix any convolution builds for this many samples. Training builds
+    // them a chunk of the batch at a time, so at most 32 samples each on every thread
+    pub fn im2col_bytes(&self, batch: usize) -> usize {
+        return self.layers.iter().map(|l| l.im2col_bytes(batch)).max().unwrap_or(0);
+    }
+
+    pub fn train(&mut self, batch: Vec<TrainingBatch<T>>, learning_rate: f32) -> Result<T, NetworkError> {
+        // Without activation the gradient for a layer is x * dL(xm, t), where dL is the loss gradient
+        // g = x * dL(xm, t)
         // y = xm
-        // e = y - t
+        // e = dL(y, t)
         // g = xe
 
-        // With an activation function it becomes 2xf'(xm)(f(xm)-t)
-        // g = xf'(xm)(f(xm)-t)
+        // With an activation function it becomes x * (f'(xm) * dL(f(xm), t))
         // y = xm
-        // e = f(y) - t
+        // e = dL(f(y), t)
         // df = f'(y)
         // r = df*e
         // g = x*r
 
-        let layer = self.get_output_layer();
-        let mut nudge: Option<Matrix> = None;
-        let len = batch.len() as f32;
+        // A softmax output trained against cross-entropy skips the Jacobian entirely
+        // r = f(y) - t
+
+        // For a hidden layer the error is the next layer's r pushed back through its weights.
+        // The bias row of those weights has no input feeding it so it is left out
+        // e = r * transpose(m without bias)
+
+        // A chunk of the batch goes through at once with a row per sample, so x * r sums every sample's gradient
+        // g = transpose(x) * r
+
+        // Every layer takes the error of its outputs and gives back the error of its inputs for the
+        // layer before, so the same goes for convolutions, pooling and the rest
+
+        if batch.is_empty() {
+            return Ok(T::zero());
+        }
+
+        // The batch is split into fixed size chunks whose gradients are added back together in order,
+        // so training gives the same weights however many threads the chunks are spread over.
+        // Each chunk seeds its own rng from its position for the same reason
+        let len = T::from_f32(batch.len() as f32);
+        let chunks = batch.chunks(GRADIENT_CHUNK).enumerate().collect::<Vec<(usize, &[TrainingBatch<T>])>>();
+        let mut results = parallel::map(&chunks, |(i, chunk)| {
+            return self.gradients(chunk, &mut ChaChaRng::from_seed(&[self.seed, self.steps, *i as u32]));
+        }).into_iter().collect::<Result<Vec<(Vec<Matrix<T>>, T)>, NetworkError>>()?.into_iter();
+        let (mut nudges, mut loss) = results.next().unwrap();
 
-        for b in batch {
-            let x = Matrix::extend_rows(&Matrix::from_vec(b.input), vec![1.]).unwrap();
-            let t = Matrix::from_vec(b.expected);
-            let y = Matrix::matrix_multiplication(&x, &layer.weights).unwrap();
-            let fy = Relu::activate(&y);
-            let e = Matrix::subtraction(&fy, &t).unwrap();
-            let df = Relu::derivative(&y);
-            let r = Matrix::hadamard(&df, &e).unwrap();
-            let g = Matrix::matrix_multiplication(&Matrix::transposition(&x), &r).unwrap();
+        for (chunk_nudges, chunk_loss) in results {
+            for (nudge, chunk_nudge) in nudges.iter_mut().zip(chunk_nudges.iter()) {
+                *nudge += chunk_nudge;
+            }
 
-            nudge = match nudge {
-                None => Some(g),
-                Some(p) => Some(Matrix::addition(&p, &g).unwrap()),
-            };
+            loss += chunk_loss;
         }
 
-        let average_nudge = Matrix::map(&nudge.unwrap(), |v| {
-            return v / len;
-        });
+        for (i, (parameters, mut nudge)) in self.layers.iter_mut().flat_map(|l| l.parameters_mut()).zip(nudges).enumerate() {
+            nudge.map_in_place(|v| {
+                return *v / len;
+            });
 
-        self.layers[0].adjust_weights(&Matrix::scalar_multiplication(&average_nudge, -learning_rate));
+            self.optimizer.update(i, parameters, &nudge, learning_rate);
+        }
+
+        self.steps = self.steps.wrapping_add(1);
+
+        return Ok(loss / len);
     }
 
-    pub fn get_output_layer(&self) -> &Layer {
-        return &self.layers.last().unwrap();
+    // The summed gradient for every parameter over the chunk, in layer order, along with the chunk's total loss
+    fn gradients(&self, chunk: &[TrainingBatch<T>], rng: &mut dyn Rng) -> Result<(Vec<Matrix<T>>, T), NetworkError> {
+        let t = Matrix::from_rows(chunk.iter().map(|b| b.expected.clone()).collect::<Vec<Vec<T>>>())?;
+        let mut xs = vec![];
+        let mut saved: Vec<Saved<T>> = vec![];
+        let mut food = Tensor::from_matrix(&Matrix::from_rows(chunk.iter().map(|b| b.input.clone()).collect::<Vec<Vec<T>>>())?);
+
+        for layer in self.layers.iter() {
+            let (outputs, s) = layer.forward(&food, Some(&mut *rng))?;
+
+            xs.push(food);
+            saved.push(s);
+            food = outputs;
+        }
+
+        let outputs = food.batch_matrix()?;
+
+        if outputs.cols() != t.cols() {
+            return Err(NetworkError::ExpectedDoesNotMatchOutputs { outputs: outputs.cols(), expected: t.cols() });
+        }
+
+        let output_index = self.layers.len() - 1;
+        let expected = Tensor::create(food.shape().to_vec(), t.elements().to_vec())?;
+        let mut gradients = self.layers[output_index].output_backward(&xs[output_index], &saved[output_index], &food, &expected, self.loss.as_ref())?;
+        let mut nudges = vec![];
+
+        for i in (0..self.layers.len()).rev() {
+            if i < output_index {
+                gradients = self.layers[i].backward(&xs[i], &saved[i], &gradients.inputs)?;
+            }
+
+            nudges.extend(gradients.parameters.drain(..).rev());
+        }
+
+        nudges.reverse();
+
+        return Ok((nudges, self.loss.loss(&outputs, &t) * T::from_f32(chunk.len() as f32)));
+    }
+
+    // Only the layers carry over, so like load the result has the default loss and optimizer.
+    // None when a layer's activation can't be rebuilt by name, or for a layer of a type from
+    // outside the crate
+    pub fn convert<U: Element>(&self) -> Option<Network<U>> {
+        return Some(Network::from_layers(self.layers.iter().map(|l| Network::convert_layer::<U>(l.as_ref())).collect::<Option<Vec<Box<dyn Layer<U>>>>>()?));
+    }
+
+    fn convert_layer<U: Element>(layer: &dyn Layer<T>) -> Option<Box<dyn Layer<U>>> {
+        let layer = layer.as_any();
+
+        if let Some(l) = layer.downcast_ref::<Dense<T>>() {
+            return Some(Box::new(l.convert::<U>()?));
+        }
+
+        if let Some(l) = layer.downcast_ref::<Conv2D<T>>() {
+            return Some(Box::new(l.convert::<U>()?));
+        }
+
+        if let Some(l) = layer.downcast_ref::<LayerNorm<T>>() {
+            return Some(Box::new(l.convert::<U>()));
+        }
+
+        if let Some(l) = layer.downcast_ref::<MaxPool2D>() {
+            return Some(Box::new(l.clone()));
+        }
+
+        if let Some(l) = layer.downcast_ref::<AvgPool2D>() {
+            return Some(Box::new(l.clone()));
+        }
+
+        if let Some(l) = layer.downcast_ref::<GlobalAvgPool>() {
+            return Some(Box::new(l.clone()));
+        }
+
+        if let Some(l) = layer.downcast_ref::<Flatten>() {
+            return Some(Box::new(l.clone()));
+        }
+
+        if let Some(l) = layer.downcast_ref::<Dropout>() {
+            return Some(Box::new(l.clone()));
+        }
+
+        return None;
+    }
+
+    // Every layer type in the crate can be saved, but not ones from outside it
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SerialisationError> {
+        return fs::write(path, serialisation::to_binary(&self.layers)?).map_err(|e| SerialisationError::Io(e.kind()));
+    }
+
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<(), SerialisationError> {
+        return fs::write(path, serialisation::to_json(&self.layers)?).map_err(|e| SerialisationError::Io(e.kind()));
+    }
+
+    // Reads files written by either save or save_json. Only the layers are stored so the
+    // network comes back with the default loss and optimizer
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Network<T>, SerialisationError> {
+        let bytes = fs::read(path).map_err(|e| SerialisationError::Io(e.kind()))?;
+
+        return Ok(Network::from_layers(serialisation::read(&bytes)?));
+    }
+
+    pub fn get_output_layer(&self) -> &dyn Layer<T> {
+        return self.layers.last().unwrap().as_ref();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkError {
+    Matrix(MatrixError),
+    Tensor(TensorError),
+    // Every sample needs an expected value for each output node
+    ExpectedDoesNotMatchOutputs {
+        outputs: usize,
+        expected: usize,
+    },
+}
+
+impl From<MatrixError> for NetworkError {
+    fn from(e: MatrixError) -> NetworkError {
+        return NetworkError::Matrix(e);
+    }
+}
+
+impl From<TensorError> for NetworkError {
+    fn from(e: TensorError) -> NetworkError {
+        return NetworkError::Tensor(e);
+    }
+}
+
+impl Display for NetworkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            NetworkError::Matrix(e) => write!(f, "{}", e),
+            NetworkError::Tensor(e) => write!(f, "{}", e),
+            NetworkError::ExpectedDoesNotMatchOutputs { outputs, expected } => {
+                write!(f, "the network has {} outputs but a sample expected {}", outputs, expected)
+            },
+        };
+    }
+}
+
+impl Error for NetworkError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            NetworkError::Matrix(e) => Some(e),
+            NetworkError::Tensor(e) => Some(e),
+            _ => None,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{ChaChaRng, SeedableRng};
+    use crate::network::activation::{Identity, Relu, Sigmoid, Softmax, Tanh};
+    use crate::network::initialiser::Constant;
+    use crate::network::matrix::{Matrix, MatrixError};
+    use crate::network::serialisation::SerialisationError;
+    use crate::network::{Conv2D, Dense, Dropout, Element, F16, Flatten, GlobalAvgPool, Layer, LayerNorm, MaxPool2D, Network, NetworkError, Padding, TrainingBatch};
+
+    fn error(network: &Network, input: Vec<f32>, expected: Vec<f32>) -> f32 {
+        return network.feed_forward(input).unwrap().iter().zip(expected).map(|(o, t)| (o - t) * (o - t)).sum();
+    }
+
+    #[test]
+    fn train_updates_every_layer() {
+        let mut network = Network::from_layers(vec![
+            Box::new(Dense { weights: Matrix::create(3, 3, vec![0.5, -0.2, 0.1, 0.3, 0.8, -0.4, 0.1, 0.1, 0.2]).unwrap(), activation: Box::new(Relu {}) }),
+            Box::new(Dense { weights: Matrix::create(2, 4, vec![0.4, -0.3, 0.2, 0.6, -0.5, 0.1, 0.05, 0.05]).unwrap(), activation: Box::new(Sigmoid {}) }),
+        ]);
+        let hidden_before = network.layers[0].parameters()[0].clone();
+        let before = error(&network, vec![0.6, 0.9], vec![1., 0.]);
+
+        network.train(vec![TrainingBatch { input: vec![0.6, 0.9], expected: vec![1., 0.] }], 0.1).unwrap();
+
+        assert_ne!(network.layers[0].parameters()[0], &hidden_before);
+        assert!(error(&network, vec![0.6, 0.9], vec![1., 0.]) < before);
+    }
+
+    #[test]
+    fn create_with_initialisers() {
+        let network: Network = Network::create_with_initialisers(vec![(2, Box::new(Identity {}))], 3, &Constant::create(0.5), &Constant::create(0.1), &mut ChaChaRng::from_seed(&[1]));
+
+        assert_eq!(network.feed_forward(vec![1., 2., 3.]).unwrap(), vec![3.1, 3.1]);
+    }
+
+    #[test]
+    fn same_seed_same_network() {
+        let n1: Network = Network::create(vec![(3, Box::new(Relu {})), (2, Box::new(Sigmoid {}))], 2, &mut ChaChaRng::from_seed(&[42]));
+        let n2: Network = Network::create(vec![(3, Box::new(Relu {})), (2, Box::new(Sigmoid {}))], 2, &mut ChaChaRng::from_seed(&[42]));
+
+        for (l1, l2) in n1.layers.iter().zip(n2.layers.iter()) {
+            assert_eq!(l1.parameters(), l2.parameters());
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join("rust_cnn_save_and_load.bin");
+        let network = Network::create(vec![(3, Box::new(Relu {})), (2, Box::new(Sigmoid {}))], 2, &mut ChaChaRng::from_seed(&[7]));
+
+        network.save(&path).expect("Could not save");
+        let loaded = Network::load(&path).expect("Could not load");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.feed_forward(vec![0.3, -0.6]).unwrap(), network.feed_forward(vec![0.3, -0.6]).unwrap());
+    }
+
+    #[test]
+    fn load_missing_file() {
+        match Network::<f32>::load(std::env::temp_dir().join("rust_cnn_does_not_exist.bin")) {
+            Ok(_) => panic!("Should error"),
+            Err(e) => assert_eq!(e, SerialisationError::Io(std::io::ErrorKind::NotFound)),
+        };
+    }
+
+    #[test]
+    fn wrong_sizes_error() {
+        let mut network: Network = Network::create(vec![(3, Box::new(Relu {})), (2, Box::new(Sigmoid {}))], 2, &mut ChaChaRng::from_seed(&[4]));
+        let weights = network.layers[0].parameters()[0].clone();
+
+        assert_eq!(network.feed_forward(vec![0.3, -0.6, 0.1]), Err(NetworkError::Matrix(MatrixError::ShapesDoNotMatch {
+            operation: "matrix_multiplication",
+            left: (4, 1),
+            right: (3, 3),
+        })));
+        assert_eq!(network.train(vec![TrainingBatch { input: vec![0.3, -0.6], expected: vec![1.] }], 0.1), Err(NetworkError::ExpectedDoesNotMatchOutputs { outputs: 2, expected: 1 }));
+        assert!(network.train(vec![TrainingBatch { input: vec![0.3], expected: vec![1., 0.] }], 0.1).is_err());
+        assert_eq!(network.layers[0].parameters()[0], &weights);
+        assert_eq!(NetworkError::ExpectedDoesNotMatchOutputs { outputs: 2, expected: 1 }.to_string(), "the network has 2 outputs but a sample expected 1");
+    }
+
+    #[test]
+    fn feed_forward_batch() {
+        let network: Network = Network::create(vec![(3, Box::new(Relu {})), (2, Box::new(Softmax {}))], 2, &mut ChaChaRng::from_seed(&[8]));
+        let inputs = vec![vec![0.3, -0.6], vec![1., 0.5], vec![-0.2, 0.1]];
+        let result = network.feed_forward_batch(inputs.clone()).unwrap();
+
+        assert_eq!(result.len(), 3);
+
+        for (input, output) in inputs.into_iter().zip(result) {
+            let expected = network.feed_forward(input).unwrap();
+
+            for (o, e) in output.iter().zip(expected.iter()) {
+                assert!((o - e).abs() < 0.000001);
+            }
+        }
+
+        assert_eq!(network.feed_forward_batch(vec![]).unwrap(), Vec::<Vec<f32>>::new());
+        assert!(network.feed_forward_batch(vec![vec![0.3, -0.6, 1.], vec![0.5], vec![-0.2, 0.1]]).is_err());
+    }
+
+    #[test]
+    fn train_averages_the_batch() {
+        let create = || -> Network { Network::create(vec![(3, Box::new(Relu {})), (2, Box::new(Sigmoid {}))], 2, &mut ChaChaRng::from_seed(&[9])) };
+        let a = || TrainingBatch { input: vec![0.6, 0.9], expected: vec![1., 0.] };
+        let b = || TrainingBatch { input: vec![-0.4, 0.2], expected: vec![0., 1.] };
+        let mut only_a = create();
+        let mut only_b = create();
+        let mut both = create();
+
+        only_a.train(vec![a()], 0.1).unwrap();
+        only_b.train(vec![b()], 0.1).unwrap();
+        both.train(vec![a(), b()], 0.1).unwrap();
+
+        for i in 0..both.layers.len() {
+            let average = Matrix::scalar_multiplication(&Matrix::addition(&only_a.layers[i].parameters()[0], &only_b.layers[i].parameters()[0]).unwrap(), 0.5);
+
+            for (result, expected) in both.layers[i].parameters()[0].elements().iter().zip(average.elements().iter()) {
+                assert!((result - expected).abs() < 0.000001);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn train_is_the_same_on_any_number_of_threads() {
+        let batch = || (0..100).map(|i| {
+            let x = i as f32 / 100.;
+
+            return TrainingBatch { input: vec![x, 1. - x], expected: vec![x * x, 1. - x] };
+        }).collect::<Vec<TrainingBatch>>();
+        let train = |threads: usize| {
+            let mut network = Network::create(vec![(8, Box::new(Relu {})), (2, Box::new(Sigmoid {}))], 2, &mut ChaChaRng::from_seed(&[3]));
+
+            crate::network::set_threads(threads);
+            let loss = network.train(batch(), 0.1).unwrap();
+            crate::network::set_threads(0);
+
+            return (loss, network.layers.iter().map(|l| l.parameters()[0].clone()).collect::<Vec<Matrix>>());
+        };
+
+        assert_eq!(train(1), train(4));
+    }
+
+    #[test]
+    fn gradient_check_in_f64() {
+        let create = || -> Network<f64> { Network::create(vec![(3, Box::new(Tanh {})), (2, Box::new(Sigmoid {}))], 2, &mut ChaChaRng::from_seed(&[6])) };
+        let (input, expected) = (vec![0.4, -0.7], vec![0.2, 0.9]);
+        let loss = |network: &Network<f64>| network.feed_forward(input.clone()).unwrap().iter().zip(expected.iter()).map(|(o, t)| (o - t) * (o - t)).sum::<f64>() / 2.;
+        let original = create();
+        let mut trained = create();
+        let h = 0.000001;
+
+        // With plain sgd and a learning rate of 1 each weight moves by exactly its gradient
+        trained.train(vec![TrainingBatch { input: input.clone(), expected: expected.clone() }], 1.).unwrap();
+
+        for l in 0..original.layers.len() {
+            for i in 0..original.layers[l].parameters()[0].elements().len() {
+                let mut above = create();
+                let mut below = create();
+                above.layers[l].parameters_mut()[0].elements_mut()[i] += h;
+                below.layers[l].parameters_mut()[0].elements_mut()[i] -= h;
+                let slope = (loss(&above) - loss(&below)) / (2. * h);
+                let step = original.layers[l].parameters()[0].elements()[i] - trained.layers[l].parameters()[0].elements()[i];
+
+                assert!((slope - step).abs() < 0.0000001, "expected {} got {}", slope, step);
+            }
+        }
+    }
+
+    #[test]
+    fn gradient_check_with_mixed_layers() {
+        let create = || -> Network<f64> {
+            let rng = &mut ChaChaRng::from_seed(&[11]);
+            let layers: Vec<Box<dyn Layer<f64>>> = vec![
+                Box::new(Conv2D::create((2, 5, 4), 3, (3, 3), Box::new(Tanh {}), rng).with_padding(Padding::Same)),
+                Box::new(MaxPool2D::create((3, 5, 4), (2, 2))),
+                Box::new(Conv2D::create((3, 2, 2), 4, (1, 1), Box::new(Tanh {}), rng)),
+                Box::new(GlobalAvgPool::create((4, 2, 2))),
+                Box::new(Flatten {}),
+                Box::new(LayerNorm::create(4)),
+                Box::new(Dense::create(2, 4, Box::new(Sigmoid {}), rng)),
+            ];
+
+            return Network::from_layers(layers);
+        };
+        let input = (0..40).map(|i| ((i * 7) % 11) as f64 / 5. - 1. + i as f64 / 1000.).collect::<Vec<f64>>();
+        let expected = vec![0.2, 0.9];
+        let loss = |network: &Network<f64>| network.feed_forward(input.clone()).unwrap().iter().zip(expected.iter()).map(|(o, t)| (o - t) * (o - t)).sum::<f64>() / 2.;
+        let original = create();
+        let mut trained = create();
+        let h = 0.000001;
+
+        trained.train(vec![TrainingBatch { input: input.clone(), expected: expected.clone() }], 1.).unwrap();
+
+        for l in 0..original.layers.len() {
+            for p in 0..original.layers[l].parameters().len() {
+                for i in 0..original.layers[l].parameters()[p].elements().len() {
+                    let mut above = create();
+                    let mut below = create();
+                    above.layers[l].parameters_mut()[p].elements_mut()[i] += h;
+                    below.layers[l].parameters_mut()[p].elements_mut()[i] -= h;
+                    let slope = (loss(&above) - loss(&below)) / (2. * h);
+                    let step = original.layers[l].parameters()[p].elements()[i] - trained.layers[l].parameters()[p].elements()[i];
+
+                    assert!((slope - step).abs() < 0.0000001, "layer {} expected {} got {}", l, slope, step);
+                }
+            }
+        }
+
+        assert_eq!(original.output_shape(&[2, 5, 4]), vec![2]);
+        assert_eq!(original.layers().iter().map(|l| l.parameters().len()).collect::<Vec<usize>>(), vec![2, 0, 2, 0, 0, 2, 1]);
+        assert_eq!(original.im2col_bytes(4), 4 * 20 * 18 * 8);
+
+        let path = std::env::temp_dir().join("rust_cnn_mixed.json");
+        trained.save_json(&path).expect("Could not save");
+        let loaded = Network::<f64>::load(&path).expect("Could not load");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.feed_forward(input.clone()).unwrap(), trained.feed_forward(input.clone()).unwrap());
+
+        let converted = original.convert::<f32>().expect("Could not convert");
+
+        for (c, o) in converted.feed_forward(input.iter().map(|v| *v as f32).collect::<Vec<f32>>()).unwrap().iter().zip(original.feed_forward(input.clone()).unwrap()) {
+            assert!((*c as f64 - o).abs() < 0.00001);
+        }
+    }
+
+    #[test]
+    fn dropout_only_while_training() {
+        let create = |seed: u32| -> Network {
+            let rng = &mut ChaChaRng::from_seed(&[12]);
+            let layers: Vec<Box<dyn Layer>> = vec![
+                Box::new(Dense::create(16, 2, Box::new(Relu {}), rng)),
+                Box::new(Dropout::create(0.5)),
+                Box::new(Dense::create(1, 16, Box::new(Sigmoid {}), rng)),
+            ];
+
+            return Network::from_layers(layers).with_seed(seed);
+        };
+        let batch = || vec![TrainingBatch { input: vec![0.3, -0.6], expected: vec![1.] }, TrainingBatch { input: vec![0.9, 0.1], expected: vec![0.] }];
+        let train = |seed: u32| {
+            let mut network = create(seed);
+
+            network.train(batch(), 0.5).unwrap();
+            network.train(batch(), 0.5).unwrap();
+
+            return network.layers.iter().map(|l| l.parameters().into_iter().cloned().collect::<Vec<Matrix>>()).collect::<Vec<Vec<Matrix>>>();
+        };
+
+        assert_eq!(create(1).feed_forward(vec![0.3, -0.6]).unwrap(), create(2).feed_forward(vec![0.3, -0.6]).unwrap());
+        assert_eq!(train(1), train(1));
+        assert_ne!(train(1), train(2));
+    }
+
+    #[test]
+    fn convert() {
+        let network: Network = Network::create(vec![(3, Box::new(Relu {})), (2, Box::new(Sigmoid {}))], 2, &mut ChaChaRng::from_seed(&[5]));
+        let wide = network.convert::<f64>().expect("Could not convert");
+        let half = network.convert::<F16>().expect("Could not convert");
+        let back = wide.convert::<f32>().expect("Could not convert");
+        let expected = network.feed_forward(vec![0.3, -0.8]).unwrap();
+
+        for (l1, l2) in network.layers.iter().zip(back.layers.iter()) {
+            assert_eq!(l1.parameters(), l2.parameters());
+        }
+
+        for (w, e) in wide.feed_forward(vec![0.3, -0.8]).unwrap().iter().zip(expected.iter()) {
+            assert!((*w as f32 - e).abs() < 0.00001);
+        }
+
+        for (h, e) in half.feed_forward(vec![F16::from_f32(0.3), F16::from_f32(-0.8)]).unwrap().iter().zip(expected.iter()) {
+            assert!((h.to_f32() - e).abs() < 0.01);
+        }
     }
 }
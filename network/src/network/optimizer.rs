@@ -0,0 +1,137 @@
+use crate::network::matrix::{Element, Matrix};
+
+pub use self::adagrad::Adagrad;
+pub use self::adam::Adam;
+pub use self::adam_w::AdamW;
+pub use self::rms_prop::RmsProp;
+pub use self::sgd::Sgd;
+
+mod adagrad;
+mod adam;
+mod adam_w;
+mod rms_prop;
+mod sgd;
+
+// Any state an optimizer keeps is stored against the key of the parameters it was given,
+// so a single optimizer can look after every layer of a network. The parameters are
+// updated in place
+pub trait Optimizer<T: Element = f32>: Send + Sync {
+    fn update(&mut self, key: usize, parameters: &mut Matrix<T>, gradient: &Matrix<T>, learning_rate: f32) -> ();
+}
+
+// The usual epsilons round to zero in half precision, which turns a step with no gradient into
+// 0 / 0, so they are kept to at least the smallest value the element can hold
+fn epsilon<T: Element>(epsilon: f32) -> T {
+    return T::from_f32(epsilon).max(T::min_positive());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::matrix::{Element, Matrix, F16};
+    use crate::network::optimizer::{Adagrad, Adam, AdamW, Optimizer, RmsProp, Sgd};
+
+    fn minimise(optimizer: &mut dyn Optimizer, learning_rate: f32) -> Matrix {
+        // f(w) = sum(w^2) so the gradient is 2w
+        let mut w = Matrix::from_vec(vec![1.5, -2., 0.5]);
+
+        for _ in 0..500 {
+            let g = Matrix::scalar_multiplication(&w, 2.);
+
+            optimizer.update(0, &mut w, &g, learning_rate);
+        }
+
+        return w;
+    }
+
+    fn assert_minimised(optimizer: &mut dyn Optimizer, learning_rate: f32) {
+        let w = minimise(optimizer, learning_rate);
+
+        for v in w.elements() {
+            assert!(v.abs() < 0.05, "expected 0 got {}", v);
+        }
+    }
+
+    #[test]
+    fn minimises() {
+        assert_minimised(&mut Sgd::create(0.), 0.05);
+        assert_minimised(&mut Sgd::create(0.9), 0.01);
+        assert_minimised(&mut Sgd::nesterov(0.9), 0.01);
+        assert_minimised(&mut RmsProp::create(0.9), 0.01);
+        assert_minimised(&mut Adagrad::create(), 0.5);
+        assert_minimised(&mut Adam::create(0.9, 0.999), 0.05);
+        assert_minimised(&mut AdamW::create(0.9, 0.999, 0.01), 0.05);
+    }
+
+    #[test]
+    fn sgd() {
+        let mut optimizer = Sgd::create(0.);
+        let mut w = Matrix::from_vec(vec![1., 1.]);
+        optimizer.update(0, &mut w, &Matrix::from_vec(vec![2., -4.]), 0.5);
+
+        assert_eq!(w, Matrix::from_vec(vec![0., 3.]));
+    }
+
+    #[test]
+    fn sgd_momentum() {
+        let mut optimizer = Sgd::create(0.5);
+        let mut w = Matrix::from_vec(vec![1., 1.]);
+        let g = Matrix::from_vec(vec![2., -4.]);
+        optimizer.update(0, &mut w, &g, 0.5);
+        optimizer.update(0, &mut w, &g, 0.5);
+
+        // The second step moves by the gradient plus half the first step
+        assert_eq!(w, Matrix::from_vec(vec![-1.5, 6.]));
+    }
+
+    #[test]
+    fn state_is_kept_per_key() {
+        let mut optimizer = Sgd::create(0.5);
+        let mut w1 = Matrix::from_vec(vec![1., 1.]);
+        let mut w2 = Matrix::from_vec(vec![1., 1.]);
+        let g = Matrix::from_vec(vec![2., -4.]);
+        optimizer.update(0, &mut w1, &g, 0.5);
+        optimizer.update(1, &mut w2, &g, 0.5);
+
+        assert_eq!(w2, Matrix::from_vec(vec![0., 3.]));
+    }
+
+    #[test]
+    fn adam_first_step() {
+        let mut optimizer = Adam::create(0.9, 0.999);
+        let mut w: Matrix = Matrix::from_vec(vec![1., 1.]);
+        optimizer.update(0, &mut w, &Matrix::from_vec(vec![0.02, -300.]), 0.1);
+
+        assert!((w.elements()[0] - 0.9).abs() < 0.0001);
+        assert!((w.elements()[1] - 1.1).abs() < 0.0001);
+    }
+
+    #[test]
+    fn adam_w_decays_weights() {
+        let mut optimizer = AdamW::create(0.9, 0.999, 0.5);
+        let mut w: Matrix = Matrix::from_vec(vec![2., -2.]);
+        optimizer.update(0, &mut w, &Matrix::from_vec(vec![0., 0.]), 0.1);
+
+        assert!((w.elements()[0] - 1.9).abs() < 0.000001);
+        assert!((w.elements()[1] + 1.9).abs() < 0.000001);
+    }
+
+    #[test]
+    fn half_precision_without_gradient() {
+        let optimizers: Vec<Box<dyn Optimizer<F16>>> = vec![Box::new(Adam::create(0.9, 0.999)), Box::new(Adagrad::create()), Box::new(RmsProp::create(0.9))];
+
+        for mut optimizer in optimizers {
+            let mut w = Matrix::from_vec(vec![F16::from_f32(1.), F16::from_f32(1.)]);
+            optimizer.update(0, &mut w, &Matrix::from_vec(vec![F16::zero(), F16::from_f32(0.5)]), 0.1);
+
+            // The parameter without a gradient stays put rather than going to NaN
+            assert_eq!(w.elements()[0].to_f32(), 1.);
+            assert!(w.elements()[1].to_f32() < 1.);
+        }
+
+        let mut adam = Adam::create(0.9, 0.999);
+        let mut w = Matrix::from_vec(vec![F16::from_f32(1.)]);
+        adam.update(0, &mut w, &Matrix::from_vec(vec![F16::from_f32(0.5)]), 0.1);
+
+        assert!((w.elements()[0].to_f32() - 0.9).abs() < 0.001);
+    }
+}
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+use crate::network::matrix::{Element, Matrix};
+use crate::network::optimizer::{self, Optimizer};
+
+pub struct Adagrad<T = f32> {
+    pub epsilon: f32,
+    sum_squares: HashMap<usize, Matrix<T>>,
+}
+
+impl<T: Element> Adagrad<T> {
+    pub fn create() -> Adagrad<T> {
+        return Adagrad {
+            epsilon: 0.00000001,
+            sum_squares: HashMap::new(),
+        };
+    }
+}
+
+impl<T: Element> Optimizer<T> for Adagrad<T> {
+    fn update(&mut self, key: usize, parameters: &mut Matrix<T>, gradient: &Matrix<T>, learning_rate: f32) -> () {
+        let (epsilon, learning_rate) = (optimizer::epsilon::<T>(self.epsilon), T::from_f32(learning_rate));
+
+        // s = s + g^2
+        let sum_square = self.sum_squares.entry(key).or_insert_with(|| Matrix::map(gradient, |_| T::zero()));
+
+        for (s, g) in sum_square.elements_mut().iter_mut().zip(gradient.elements().iter()) {
+            *s += *g * *g;
+        }
+
+        // w = w - lr * g / (sqrt(s) + ε)
+        for ((w, g), s) in parameters.elements_mut().iter_mut().zip(gradient.elements().iter()).zip(sum_square.elements().iter()) {
+            *w -= learning_rate * *g / (s.sqrt() + epsilon);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use crate::network::matrix::{Element, Matrix};
+use crate::network::optimizer::{self, Optimizer};
+
+pub struct Adam<T = f32> {
+    pub beta1: f32,
+    pub beta2: f32,
+    pub epsilon: f32,
+    moments: HashMap<usize, Moments<T>>,
+}
+
+struct Moments<T> {
+    first: Matrix<T>,
+    second: Matrix<T>,
+    steps: i32,
+}
+
+impl<T: Element> Adam<T> {
+    pub fn create(beta1: f32, beta2: f32) -> Adam<T> {
+        return Adam {
+            beta1,
+            beta2,
+            epsilon: 0.00000001,
+            moments: HashMap::new(),
+        };
+    }
+}
+
+impl<T: Element> Optimizer<T> for Adam<T> {
+    fn update(&mut self, key: usize, parameters: &mut Matrix<T>, gradient: &Matrix<T>, learning_rate: f32) -> () {
+        let (beta1, beta2) = (T::from_f32(self.beta1), T::from_f32(self.beta2));
+        let (epsilon, learning_rate) = (optimizer::epsilon::<T>(self.epsilon), T::from_f32(learning_rate));
+        let moments = self.moments.entry(key).or_insert_with(|| {
+            return Moments {
+                first: Matrix::map(gradient, |_| T::zero()),
+                second: Matrix::map(gradient, |_| T::zero()),
+                steps: 0,
+            };
+        });
+
+        // m = β1m + (1 - β1)g
+        // v = β2v + (1 - β2)g^2
+        for ((m, v), g) in moments.first.elements_mut().iter_mut().zip(moments.second.elements_mut().iter_mut()).zip(gradient.elements().iter()) {
+            *m = *m * beta1 + *g * (T::one() - beta1);
+            *v = *v * beta2 + *g * *g * (T::one() - beta2);
+        }
+
+        moments.steps += 1;
+
+        // Both moments start at zero so are scaled up to remove the bias towards it
+        // w = w - lr * (m / (1 - β1^t)) / (sqrt(v / (1 - β2^t)) + ε)
+        let first_correction = T::one() - beta1.powi(moments.steps);
+        let second_correction = T::one() - beta2.powi(moments.steps);
+
+        for ((w, m), v) in parameters.elements_mut().iter_mut().zip(moments.first.elements().iter()).zip(moments.second.elements().iter()) {
+            *w -= learning_rate * (*m / first_correction) / ((*v / second_correction).sqrt() + epsilon);
+        }
+    }
+}
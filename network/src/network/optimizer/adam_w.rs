@@ -0,0 +1,26 @@
+use crate::network::matrix::{Element, Matrix};
+use crate::network::optimizer::{Adam, Optimizer};
+
+// Adam with the weight decay applied straight to the parameters rather than folded into the gradient
+pub struct AdamW<T = f32> {
+    pub weight_decay: f32,
+    adam: Adam<T>,
+}
+
+impl<T: Element> AdamW<T> {
+    pub fn create(beta1: f32, beta2: f32, weight_decay: f32) -> AdamW<T> {
+        return AdamW {
+            weight_decay,
+            adam: Adam::create(beta1, beta2),
+        };
+    }
+}
+
+impl<T: Element> Optimizer<T> for AdamW<T> {
+    fn update(&mut self, key: usize, parameters: &mut Matrix<T>, gradient: &Matrix<T>, learning_rate: f32) -> () {
+        // Adam's step doesn't depend on the weights so decaying them first is the same as decaying the originals
+        // w = w - lr * λw + adam(g)
+        parameters.scale_in_place(T::from_f32(1. - learning_rate * self.weight_decay));
+        self.adam.update(key, parameters, gradient, learning_rate);
+    }
+}
@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+use crate::network::matrix::{Element, Matrix};
+use crate::network::optimizer::{self, Optimizer};
+
+pub struct RmsProp<T = f32> {
+    pub decay: f32,
+    pub epsilon: f32,
+    mean_squares: HashMap<usize, Matrix<T>>,
+}
+
+impl<T: Element> RmsProp<T> {
+    pub fn create(decay: f32) -> RmsProp<T> {
+        return RmsProp {
+            decay,
+            epsilon: 0.00000001,
+            mean_squares: HashMap::new(),
+        };
+    }
+}
+
+impl<T: Element> Optimizer<T> for RmsProp<T> {
+    fn update(&mut self, key: usize, parameters: &mut Matrix<T>, gradient: &Matrix<T>, learning_rate: f32) -> () {
+        let (decay, epsilon, learning_rate) = (T::from_f32(self.decay), optimizer::epsilon::<T>(self.epsilon), T::from_f32(learning_rate));
+        let mean_square = self.mean_squares.entry(key).or_insert_with(|| Matrix::map(gradient, |_| T::zero()));
+
+        // s = ps + (1 - p)g^2
+        for (s, g) in mean_square.elements_mut().iter_mut().zip(gradient.elements().iter()) {
+            *s = *s * decay + *g * *g * (T::one() - decay);
+        }
+
+        // w = w - lr * g / (sqrt(s) + ε)
+        for ((w, g), s) in parameters.elements_mut().iter_mut().zip(gradient.elements().iter()).zip(mean_square.elements().iter()) {
+            *w -= learning_rate * *g / (s.sqrt() + epsilon);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use crate::network::matrix::{Element, Matrix};
+use crate::network::optimizer::Optimizer;
+
+// Plain gradient descent when momentum is 0
+pub struct Sgd<T = f32> {
+    pub momentum: f32,
+    pub nesterov: bool,
+    velocities: HashMap<usize, Matrix<T>>,
+}
+
+impl<T: Element> Sgd<T> {
+    pub fn create(momentum: f32) -> Sgd<T> {
+        return Sgd {
+            momentum,
+            nesterov: false,
+            velocities: HashMap::new(),
+        };
+    }
+
+    pub fn nesterov(momentum: f32) -> Sgd<T> {
+        return Sgd {
+            momentum,
+            nesterov: true,
+            velocities: HashMap::new(),
+        };
+    }
+}
+
+impl<T: Element> Optimizer<T> for Sgd<T> {
+    fn update(&mut self, key: usize, parameters: &mut Matrix<T>, gradient: &Matrix<T>, learning_rate: f32) -> () {
+        let step = T::from_f32(-learning_rate);
+
+        if self.momentum == 0. {
+            // w = w - lr * g
+            parameters.add_scaled_in_place(gradient, step).unwrap();
+
+            return;
+        }
+
+        // v = μv - lr * g
+        let momentum = T::from_f32(self.momentum);
+        let velocity = self.velocities.entry(key).or_insert_with(|| Matrix::map(gradient, |_| T::zero()));
+        velocity.scale_in_place(momentum);
+        velocity.add_scaled_in_place(gradient, step).unwrap();
+
+        // Nesterov looks ahead along the new velocity before stepping
+        // w = w + μv - lr * g
+        match self.nesterov {
+            true => {
+                parameters.add_scaled_in_place(velocity, momentum).unwrap();
+                parameters.add_scaled_in_place(gradient, step).unwrap();
+            },
+            false => parameters.add_in_place(velocity).unwrap(),
+        };
+    }
+}
@@ -0,0 +1,113 @@
+// Spreads work across threads when the parallel feature is on and runs it in order on the
+// calling thread when it isn't. Callers split the work the same way either way and combine
+// results in order, so the thread count never changes the answer
+#[cfg(feature = "parallel")]
+use std::cell::Cell;
+#[cfg(feature = "parallel")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "parallel")]
+use std::thread;
+
+#[cfg(feature = "parallel")]
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "parallel")]
+thread_local! {
+    // Work started from a worker stays on it rather than spawning threads of its own
+    static WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+// 0 goes back to one thread per available core
+#[cfg(feature = "parallel")]
+pub fn set_threads(threads: usize) -> () {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+#[cfg(feature = "parallel")]
+fn threads() -> usize {
+    if WORKER.with(|w| w.get()) {
+        return 1;
+    }
+
+    return match THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+}
+
+// Calls f with each span of data and the span's offset into it. Spans are whole multiples of chunk_len
+#[cfg(feature = "parallel")]
+pub fn for_each_span<T, F>(data: &mut [T], chunk_len: usize, f: F) -> ()
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Sync {
+
+    let threads = threads();
+
+    if threads <= 1 || data.len() <= chunk_len {
+        f(0, data);
+
+        return;
+    }
+
+    let span_len = data.len().div_ceil(chunk_len).div_ceil(threads) * chunk_len;
+    let f = &f;
+
+    thread::scope(|scope| {
+        for (i, span) in data.chunks_mut(span_len).enumerate() {
+            scope.spawn(move || {
+                WORKER.with(|w| w.set(true));
+                f(i * span_len, span);
+            });
+        }
+    });
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn for_each_span<T, F>(data: &mut [T], _chunk_len: usize, f: F) -> ()
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Sync {
+
+    f(0, data);
+}
+
+// Results come back in the same order as items
+#[cfg(feature = "parallel")]
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync {
+
+    let threads = threads();
+
+    if threads <= 1 || items.len() <= 1 {
+        return items.iter().map(f).collect::<Vec<R>>();
+    }
+
+    let span_len = items.len().div_ceil(threads);
+    let f = &f;
+
+    return thread::scope(|scope| {
+        let handles = items.chunks(span_len).map(|span| {
+            return scope.spawn(move || {
+                WORKER.with(|w| w.set(true));
+
+                return span.iter().map(f).collect::<Vec<R>>();
+            });
+        }).collect::<Vec<_>>();
+
+        return handles.into_iter().flat_map(|h| h.join().unwrap()).collect::<Vec<R>>();
+    });
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync {
+
+    return items.iter().map(f).collect::<Vec<R>>();
+}
@@ -0,0 +1,425 @@
+use std::any::Any;
+use rand::Rng;
+use crate::network::layer::{self, Gradients, Layer, Saved};
+use crate::network::matrix::Element;
+use crate::network::spatial;
+use crate::network::tensor::{Tensor, TensorError};
+use crate::network::NetworkError;
+
+// Keeps the largest input in each window of every channel. The stride defaults to the window so
+// the windows don't overlap
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaxPool2D {
+    input: (usize, usize, usize),
+    window: (usize, usize),
+    stride: (usize, usize),
+}
+
+// Averages the inputs in each window of every channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvgPool2D {
+    input: (usize, usize, usize),
+    window: (usize, usize),
+    stride: (usize, usize),
+}
+
+// Averages each whole channel down to a single value, giving (channels, 1, 1)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalAvgPool {
+    input: (usize, usize, usize),
+}
+
+impl MaxPool2D {
+    // input is (channels, height, width) and window is (height, width)
+    pub fn create(input: (usize, usize, usize), window: (usize, usize)) -> MaxPool2D {
+        assert!(window.0 > 0 && window.1 > 0, "MaxPool2D window {:?} needs to be at least 1 on each side", window);
+
+        return MaxPool2D {
+            input,
+            window,
+            stride: window,
+        };
+    }
+
+    pub fn with_stride(mut self, stride: (usize, usize)) -> MaxPool2D {
+        assert!(stride.0 > 0 && stride.1 > 0, "MaxPool2D stride {:?} needs to be at least 1 on each side", stride);
+        self.stride = stride;
+
+        return self;
+    }
+
+    pub fn input_shape(&self) -> (usize, usize, usize) {
+        return self.input;
+    }
+
+    // (height, width)
+    pub fn window(&self) -> (usize, usize) {
+        return self.window;
+    }
+
+    pub fn stride(&self) -> (usize, usize) {
+        return self.stride;
+    }
+
+    pub fn output_shape(&self) -> (usize, usize, usize) {
+        return output_shape(self.input, self.window, self.stride);
+    }
+
+    pub fn feed_forward<T: Element>(&self, inputs: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        return Ok(self.pool(inputs)?.0);
+    }
+
+    // The outputs along with where each one was taken from in the inputs, which is the only
+    // input backward passes the error on to. The first of equal inputs wins
+    pub fn pool<T: Element>(&self, inputs: &Tensor<T>) -> Result<(Tensor<T>, Vec<usize>), TensorError> {
+        let batch = spatial::check_inputs("max_pool2d", inputs, self.input)?;
+        let x = inputs.to_vec();
+        let (channels, height, width) = self.output_shape();
+        let mut positions: Vec<Option<usize>> = vec![None; batch * channels * height * width];
+
+        windows(self.input, self.window, self.stride, batch, |o, i| {
+            if positions[o].is_none_or(|p| x[i] > x[p]) {
+                positions[o] = Some(i);
+            }
+        });
+
+        let positions = positions.into_iter().map(|p| p.unwrap()).collect::<Vec<usize>>();
+        let outputs = Tensor::create(vec![batch, channels, height, width], positions.iter().map(|p| x[*p]).collect::<Vec<T>>())?;
+
+        return Ok((outputs, positions));
+    }
+
+    // error is the gradient of the loss with respect to the outputs and positions is what pool
+    // recorded on the way forward
+    pub fn backward<T: Element>(&self, inputs: &Tensor<T>, positions: &[usize], error: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        let batch = spatial::check_inputs("max_pool2d", inputs, self.input)?;
+        spatial::check_error("max_pool2d", error, batch, self.output_shape())?;
+        let mut input_error = vec![T::zero(); inputs.len()];
+
+        for (p, e) in positions.iter().zip(error.to_vec()) {
+            input_error[*p] += e;
+        }
+
+        return Tensor::create(inputs.shape().to_vec(), input_error);
+    }
+}
+
+impl AvgPool2D {
+    // input is (channels, height, width) and window is (height, width)
+    pub fn create(input: (usize, usize, usize), window: (usize, usize)) -> AvgPool2D {
+        assert!(window.0 > 0 && window.1 > 0, "AvgPool2D window {:?} needs to be at least 1 on each side", window);
+
+        return AvgPool2D {
+            input,
+            window,
+            stride: window,
+        };
+    }
+
+    pub fn with_stride(mut self, stride: (usize, usize)) -> AvgPool2D {
+        assert!(stride.0 > 0 && stride.1 > 0, "AvgPool2D stride {:?} needs to be at least 1 on each side", stride);
+        self.stride = stride;
+
+        return self;
+    }
+
+    pub fn input_shape(&self) -> (usize, usize, usize) {
+        return self.input;
+    }
+
+    // (height, width)
+    pub fn window(&self) -> (usize, usize) {
+        return self.window;
+    }
+
+    pub fn stride(&self) -> (usize, usize) {
+        return self.stride;
+    }
+
+    pub fn output_shape(&self) -> (usize, usize, usize) {
+        return output_shape(self.input, self.window, self.stride);
+    }
+
+    pub fn feed_forward<T: Element>(&self, inputs: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        return self.average("avg_pool2d", inputs);
+    }
+
+    // Every input in a window gets an equal share of the window's error
+    pub fn backward<T: Element>(&self, inputs: &Tensor<T>, error: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        return self.spread("avg_pool2d", inputs, error);
+    }
+
+    // The operation is who to blame in errors, as a global pool is one big window
+    fn average<T: Element>(&self, operation: &'static str, inputs: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        let batch = spatial::check_inputs(operation, inputs, self.input)?;
+        let x = inputs.to_vec();
+        let (channels, height, width) = self.output_shape();
+        let size = T::from_f32((self.window.0 * self.window.1) as f32);
+        let mut outputs = vec![T::zero(); batch * channels * height * width];
+
+        windows(self.input, self.window, self.stride, batch, |o, i| {
+            outputs[o] += x[i] / size;
+        });
+
+        return Tensor::create(vec![batch, channels, height, width], outputs);
+    }
+
+    fn spread<T: Element>(&self, operation: &'static str, inputs: &Tensor<T>, error: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        let batch = spatial::check_inputs(operation, inputs, self.input)?;
+        spatial::check_error(operation, error, batch, self.output_shape())?;
+        let e = error.to_vec();
+        let size = T::from_f32((self.window.0 * self.window.1) as f32);
+        let mut input_error = vec![T::zero(); inputs.len()];
+
+        windows(self.input, self.window, self.stride, batch, |o, i| {
+            input_error[i] += e[o] / size;
+        });
+
+        return Tensor::create(inputs.shape().to_vec(), input_error);
+    }
+}
+
+impl GlobalAvgPool {
+    pub fn create(input: (usize, usize, usize)) -> GlobalAvgPool {
+        assert!(input.1 > 0 && input.2 > 0, "GlobalAvgPool input {:?} needs to be at least 1 high and wide", input);
+
+        return GlobalAvgPool {
+            input,
+        };
+    }
+
+    pub fn input_shape(&self) -> (usize, usize, usize) {
+        return self.input;
+    }
+
+    pub fn output_shape(&self) -> (usize, usize, usize) {
+        return (self.input.0, 1, 1);
+    }
+
+    pub fn feed_forward<T: Element>(&self, inputs: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        let (_, height, width) = self.input;
+
+        return AvgPool2D::create(self.input, (height, width)).average("global_avg_pool", inputs);
+    }
+
+    pub fn backward<T: Element>(&self, inputs: &Tensor<T>, error: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        let (_, height, width) = self.input;
+
+        return AvgPool2D::create(self.input, (height, width)).spread("global_avg_pool", inputs, error);
+    }
+}
+
+impl<T: Element> Layer<T> for MaxPool2D {
+    fn output_shape(&self, _input: &[usize]) -> Vec<usize> {
+        let (channels, height, width) = MaxPool2D::output_shape(self);
+
+        return vec![channels, height, width];
+    }
+
+    fn forward(&self, inputs: &Tensor<T>, _rng: Option<&mut dyn Rng>) -> Result<(Tensor<T>, Saved<T>), NetworkError> {
+        let (outputs, positions) = self.pool(&images(inputs, self.input)?)?;
+
+        return Ok((outputs, Saved::positions(positions)));
+    }
+
+    fn backward(&self, inputs: &Tensor<T>, saved: &Saved<T>, error: &Tensor<T>) -> Result<Gradients<T>, NetworkError> {
+        let input_error = MaxPool2D::backward(self, &images(inputs, self.input)?, &saved.positions, error)?;
+
+        return Ok(Gradients { inputs: input_error.reshape(inputs.shape().to_vec())?, parameters: vec![] });
+    }
+
+    fn name(&self) -> &'static str {
+        return "max_pool2d";
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
+
+impl<T: Element> Layer<T> for AvgPool2D {
+    fn output_shape(&self, _input: &[usize]) -> Vec<usize> {
+        let (channels, height, width) = AvgPool2D::output_shape(self);
+
+        return vec![channels, height, width];
+    }
+
+    fn forward(&self, inputs: &Tensor<T>, _rng: Option<&mut dyn Rng>) -> Result<(Tensor<T>, Saved<T>), NetworkError> {
+        return Ok((self.feed_forward(&images(inputs, self.input)?)?, Saved::none()));
+    }
+
+    fn backward(&self, inputs: &Tensor<T>, _saved: &Saved<T>, error: &Tensor<T>) -> Result<Gradients<T>, NetworkError> {
+        let input_error = AvgPool2D::backward(self, &images(inputs, self.input)?, error)?;
+
+        return Ok(Gradients { inputs: input_error.reshape(inputs.shape().to_vec())?, parameters: vec![] });
+    }
+
+    fn name(&self) -> &'static str {
+        return "avg_pool2d";
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
+
+impl<T: Element> Layer<T> for GlobalAvgPool {
+    fn output_shape(&self, _input: &[usize]) -> Vec<usize> {
+        return vec![self.input.0, 1, 1];
+    }
+
+    fn forward(&self, inputs: &Tensor<T>, _rng: Option<&mut dyn Rng>) -> Result<(Tensor<T>, Saved<T>), NetworkError> {
+        return Ok((self.feed_forward(&images(inputs, self.input)?)?, Saved::none()));
+    }
+
+    fn backward(&self, inputs: &Tensor<T>, _saved: &Saved<T>, error: &Tensor<T>) -> Result<Gradients<T>, NetworkError> {
+        let input_error = GlobalAvgPool::backward(self, &images(inputs, self.input)?, error)?;
+
+        return Ok(Gradients { inputs: input_error.reshape(inputs.shape().to_vec())?, parameters: vec![] });
+    }
+
+    fn name(&self) -> &'static str {
+        return "global_avg_pool";
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
+
+fn images<T: Element>(inputs: &Tensor<T>, (channels, height, width): (usize, usize, usize)) -> Result<Tensor<T>, NetworkError> {
+    return layer::samples(inputs, &[channels, height, width]);
+}
+
+fn output_shape(input: (usize, usize, usize), window: (usize, usize), stride: (usize, usize)) -> (usize, usize, usize) {
+    let (channels, height, width) = input;
+    let fit = |size: usize, window: usize, stride: usize| size.checked_sub(window).map_or(0, |room| room / stride + 1);
+
+    return (channels, fit(height, window.0, stride.0), fit(width, window.1, stride.1));
+}
+
+// Calls visit with the index of every output and each input in its window
+fn windows<F: FnMut(usize, usize)>(input: (usize, usize, usize), window: (usize, usize), stride: (usize, usize), batch: usize, mut visit: F) -> () {
+    let (channels, height, width) = input;
+    let (_, out_height, out_width) = output_shape(input, window, stride);
+
+    for plane in 0..(batch * channels) {
+        for oh in 0..out_height {
+            for ow in 0..out_width {
+                let o = (plane * out_height + oh) * out_width + ow;
+
+                for i in 0..window.0 {
+                    for j in 0..window.1 {
+                        visit(o, (plane * height + oh * stride.0 + i) * width + ow * stride.1 + j);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::pooling::{AvgPool2D, GlobalAvgPool, MaxPool2D};
+    use crate::network::tensor::{Tensor, TensorError};
+
+    fn input() -> Tensor {
+        // Two channels of 4x4
+        return Tensor::create(vec![1, 2, 4, 4], vec![
+            1., 5., 2., 0.,
+            3., 4., 8., 8.,
+            0., -1., 6., 2.,
+            7., 2., 1., 3.,
+
+            -1., -2., -3., -4.,
+            -5., -6., -7., -8.,
+            9., 9., 0., 1.,
+            2., 3., 4., 5.,
+        ]).unwrap();
+    }
+
+    #[test]
+    fn max_pool() {
+        let pool = MaxPool2D::create((2, 4, 4), (2, 2));
+        let (outputs, positions) = pool.pool(&input()).unwrap();
+
+        assert_eq!(pool.output_shape(), (2, 2, 2));
+        assert_eq!(outputs.to_vec(), vec![5., 8., 7., 6., -1., -3., 9., 5.]);
+        assert_eq!(positions, vec![1, 6, 12, 10, 16, 18, 24, 31]);
+
+        let error = Tensor::create(vec![1, 2, 2, 2], vec![1., 2., 3., 4., 5., 6., 7., 8.]).unwrap();
+        let input_error = pool.backward(&input(), &positions, &error).unwrap().to_vec();
+
+        assert_eq!(input_error[1], 1.);
+        assert_eq!(input_error[6], 2.);
+        assert_eq!(input_error[7], 0.);
+        assert_eq!(input_error[24], 7.);
+        assert_eq!(input_error.iter().sum::<f32>(), 36.);
+    }
+
+    #[test]
+    fn overlapping_max_pool() {
+        let pool = MaxPool2D::create((2, 4, 4), (3, 3)).with_stride((1, 1));
+        let (outputs, positions) = pool.pool(&input()).unwrap();
+        let error = Tensor::create(vec![1, 2, 2, 2], vec![1.; 8]).unwrap();
+
+        assert_eq!(outputs.to_vec(), vec![8., 8., 8., 8., 9., 9., 9., 9.]);
+
+        // Windows that share a maximum both pass their error to it
+        assert_eq!(pool.backward(&input(), &positions, &error).unwrap().get(&[0, 0, 1, 2]), 4.);
+    }
+
+    #[test]
+    fn avg_pool() {
+        let pool = AvgPool2D::create((2, 4, 4), (2, 2)).with_stride((2, 1));
+        let outputs = pool.feed_forward(&input()).unwrap();
+
+        assert_eq!(pool.output_shape(), (2, 2, 3));
+        assert_eq!(outputs.to_vec()[0..3], [3.25, 4.75, 4.5]);
+        assert_eq!(outputs.get(&[0, 1, 1, 2]), 2.5);
+
+        let error = Tensor::create(vec![1, 2, 2, 3], vec![4.; 12]).unwrap();
+        let input_error = pool.backward(&input(), &error).unwrap();
+
+        assert_eq!(input_error.get(&[0, 0, 0, 0]), 1.);
+        assert_eq!(input_error.get(&[0, 0, 0, 1]), 2.);
+        assert_eq!(input_error.to_vec().iter().sum::<f32>(), 48.);
+    }
+
+    #[test]
+    fn global_avg_pool() {
+        let pool = GlobalAvgPool::create((2, 4, 4));
+        let outputs = pool.feed_forward(&input()).unwrap();
+
+        assert_eq!(pool.output_shape(), (2, 1, 1));
+        assert_eq!(outputs.shape(), &[1, 2, 1, 1]);
+        assert_eq!(outputs.to_vec(), vec![3.1875, -0.1875]);
+        assert_eq!(pool.backward(&input(), &Tensor::create(vec![1, 2, 1, 1], vec![16., 32.]).unwrap()).unwrap().get(&[0, 1, 3, 3]), 2.);
+        assert_eq!(pool.feed_forward(&Tensor::<f32>::zeros(vec![1, 2, 4, 3])).err(), Some(TensorError::ShapesDoNotMatch { operation: "global_avg_pool", expected: vec![1, 2, 4, 4], shape: vec![1, 2, 4, 3] }));
+        assert_eq!(pool.backward(&input(), &Tensor::create(vec![1, 2, 1], vec![16., 32.]).unwrap()).err(), Some(TensorError::ShapesDoNotMatch { operation: "global_avg_pool", expected: vec![1, 2, 1, 1], shape: vec![1, 2, 1] }));
+    }
+
+    #[test]
+    #[should_panic(expected = "MaxPool2D window (0, 2) needs to be at least 1 on each side")]
+    fn zero_window_panics() {
+        MaxPool2D::create((2, 4, 4), (0, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "AvgPool2D stride (1, 0) needs to be at least 1 on each side")]
+    fn zero_stride_panics() {
+        AvgPool2D::create((2, 4, 4), (2, 2)).with_stride((1, 0));
+    }
+
+    #[test]
+    fn error_must_match_outputs() {
+        let max = MaxPool2D::create((2, 4, 4), (2, 2));
+        let (_, positions) = max.pool(&input()).unwrap();
+        let short = Tensor::create(vec![1, 2, 2, 1], vec![1.; 4]).unwrap();
+        let long = Tensor::create(vec![2, 2, 2, 2], vec![1.; 16]).unwrap();
+
+        assert_eq!(max.backward(&input(), &positions, &short).err(), Some(TensorError::ShapesDoNotMatch { operation: "max_pool2d", expected: vec![1, 2, 2, 2], shape: vec![1, 2, 2, 1] }));
+        assert_eq!(max.backward(&input(), &positions, &long).err(), Some(TensorError::ShapesDoNotMatch { operation: "max_pool2d", expected: vec![1, 2, 2, 2], shape: vec![2, 2, 2, 2] }));
+        assert_eq!(AvgPool2D::create((2, 4, 4), (2, 2)).backward(&input(), &short).err(), Some(TensorError::ShapesDoNotMatch { operation: "avg_pool2d", expected: vec![1, 2, 2, 2], shape: vec![1, 2, 2, 1] }));
+    }
+}
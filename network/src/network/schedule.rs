@@ -0,0 +1,105 @@
+pub use self::cosine_annealing::CosineAnnealing;
+pub use self::exponential_decay::ExponentialDecay;
+pub use self::linear_warmup::LinearWarmup;
+pub use self::reduce_on_plateau::ReduceOnPlateau;
+pub use self::step_decay::StepDecay;
+
+mod cosine_annealing;
+mod exponential_decay;
+mod linear_warmup;
+mod reduce_on_plateau;
+mod step_decay;
+
+// The step can count batches or epochs, whichever the training loop advances the schedule by.
+// Schedules that react to training are told the loss of each step through record
+pub trait Schedule {
+    fn learning_rate(&self, step: usize) -> f32;
+
+    fn record(&mut self, _loss: f32) -> () {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::schedule::{CosineAnnealing, ExponentialDecay, LinearWarmup, ReduceOnPlateau, Schedule, StepDecay};
+
+    fn assert_close(result: f32, expected: f32) {
+        assert!((result - expected).abs() < 0.00001, "expected {} got {}", expected, result);
+    }
+
+    #[test]
+    fn step_decay() {
+        let schedule = StepDecay::create(0.8, 0.5, 10);
+
+        assert_close(schedule.learning_rate(0), 0.8);
+        assert_close(schedule.learning_rate(9), 0.8);
+        assert_close(schedule.learning_rate(10), 0.4);
+        assert_close(schedule.learning_rate(25), 0.2);
+
+        assert_close(StepDecay::create(0.8, 0.5, 0).learning_rate(2), 0.2);
+    }
+
+    #[test]
+    fn exponential_decay() {
+        let schedule = ExponentialDecay::create(1., 0.9);
+
+        assert_close(schedule.learning_rate(0), 1.);
+        assert_close(schedule.learning_rate(2), 0.81);
+    }
+
+    #[test]
+    fn cosine_annealing() {
+        let schedule = CosineAnnealing::create(1., 0., 10, 1);
+
+        assert_close(schedule.learning_rate(0), 1.);
+        assert_close(schedule.learning_rate(5), 0.5);
+        assert_close(schedule.learning_rate(10), 1.);
+    }
+
+    #[test]
+    fn cosine_annealing_longer_restarts() {
+        let schedule = CosineAnnealing::create(1., 0., 10, 2);
+
+        assert_close(schedule.learning_rate(10), 1.);
+        assert_close(schedule.learning_rate(20), 0.5);
+        assert_close(schedule.learning_rate(30), 1.);
+    }
+
+    #[test]
+    fn cosine_annealing_zero_period() {
+        let schedule = CosineAnnealing::create(1., 0., 0, 2);
+
+        // Cycles of 1, 2 then 4 steps
+        assert_close(schedule.learning_rate(0), 1.);
+        assert_close(schedule.learning_rate(2), 0.5);
+        assert_close(schedule.learning_rate(3), 1.);
+    }
+
+    #[test]
+    fn linear_warmup() {
+        let schedule = LinearWarmup::create(4, Box::new(ExponentialDecay::create(1., 0.5)));
+
+        assert_close(schedule.learning_rate(0), 0.25);
+        assert_close(schedule.learning_rate(3), 1.);
+        assert_close(schedule.learning_rate(4), 1.);
+        assert_close(schedule.learning_rate(5), 0.5);
+    }
+
+    #[test]
+    fn reduce_on_plateau() {
+        let mut schedule = ReduceOnPlateau::create(1., 0.5, 2);
+
+        schedule.record(1.);
+        schedule.record(0.9);
+        schedule.record(0.95);
+        schedule.record(0.9);
+        assert_close(schedule.learning_rate(4), 1.);
+
+        schedule.record(0.91);
+        assert_close(schedule.learning_rate(5), 0.5);
+
+        schedule.record(0.8);
+        schedule.record(0.85);
+        schedule.record(0.85);
+        assert_close(schedule.learning_rate(8), 0.5);
+    }
+}
@@ -0,0 +1,38 @@
+use std::f32::consts::PI;
+use crate::network::schedule::Schedule;
+
+// Follows a cosine from maximum down to minimum then restarts, with each cycle
+// lasting multiplier times longer than the one before. A period of 0 is taken as 1
+pub struct CosineAnnealing {
+    pub maximum: f32,
+    pub minimum: f32,
+    pub period: usize,
+    pub multiplier: usize,
+}
+
+impl CosineAnnealing {
+    pub fn create(maximum: f32, minimum: f32, period: usize, multiplier: usize) -> CosineAnnealing {
+        return CosineAnnealing {
+            maximum,
+            minimum,
+            period,
+            multiplier,
+        };
+    }
+}
+
+impl Schedule for CosineAnnealing {
+    fn learning_rate(&self, step: usize) -> f32 {
+        let mut position = step;
+        let mut period = self.period.max(1);
+
+        while position >= period {
+            position -= period;
+            period *= self.multiplier.max(1);
+        }
+
+        let progress = (position as f32) / (period as f32);
+
+        return self.minimum + 0.5 * (self.maximum - self.minimum) * (1. + (PI * progress).cos());
+    }
+}
@@ -0,0 +1,21 @@
+use crate::network::schedule::Schedule;
+
+pub struct ExponentialDecay {
+    pub initial: f32,
+    pub rate: f32,
+}
+
+impl ExponentialDecay {
+    pub fn create(initial: f32, rate: f32) -> ExponentialDecay {
+        return ExponentialDecay {
+            initial,
+            rate,
+        };
+    }
+}
+
+impl Schedule for ExponentialDecay {
+    fn learning_rate(&self, step: usize) -> f32 {
+        return self.initial * self.rate.powi(step as i32);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::network::schedule::Schedule;
+
+// Ramps up to the wrapped schedule's starting rate over the warmup steps then hands over to it
+pub struct LinearWarmup {
+    pub warmup_steps: usize,
+    schedule: Box<dyn Schedule>,
+}
+
+impl LinearWarmup {
+    pub fn create(warmup_steps: usize, schedule: Box<dyn Schedule>) -> LinearWarmup {
+        return LinearWarmup {
+            warmup_steps,
+            schedule,
+        };
+    }
+}
+
+impl Schedule for LinearWarmup {
+    fn learning_rate(&self, step: usize) -> f32 {
+        if step < self.warmup_steps {
+            return self.schedule.learning_rate(0) * ((step + 1) as f32) / (self.warmup_steps as f32);
+        }
+
+        return self.schedule.learning_rate(step - self.warmup_steps);
+    }
+
+    fn record(&mut self, loss: f32) -> () {
+        self.schedule.record(loss);
+    }
+}
@@ -0,0 +1,46 @@
+use crate::network::schedule::Schedule;
+
+// Multiplies the learning rate by factor once the recorded loss has gone more than patience steps without improving
+pub struct ReduceOnPlateau {
+    pub factor: f32,
+    pub patience: usize,
+    pub minimum: f32,
+    learning_rate: f32,
+    best: f32,
+    steps_without_improvement: usize,
+}
+
+impl ReduceOnPlateau {
+    pub fn create(learning_rate: f32, factor: f32, patience: usize) -> ReduceOnPlateau {
+        return ReduceOnPlateau {
+            factor,
+            patience,
+            minimum: 0.,
+            learning_rate,
+            best: f32::INFINITY,
+            steps_without_improvement: 0,
+        };
+    }
+}
+
+impl Schedule for ReduceOnPlateau {
+    fn learning_rate(&self, _step: usize) -> f32 {
+        return self.learning_rate;
+    }
+
+    fn record(&mut self, loss: f32) -> () {
+        if loss < self.best {
+            self.best = loss;
+            self.steps_without_improvement = 0;
+
+            return;
+        }
+
+        self.steps_without_improvement += 1;
+
+        if self.steps_without_improvement > self.patience {
+            self.learning_rate = (self.learning_rate * self.factor).max(self.minimum);
+            self.steps_without_improvement = 0;
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::network::schedule::Schedule;
+
+// Multiplies the learning rate by factor every step_size steps, where a step_size of 0 is taken as 1
+pub struct StepDecay {
+    pub initial: f32,
+    pub factor: f32,
+    pub step_size: usize,
+}
+
+impl StepDecay {
+    pub fn create(initial: f32, factor: f32, step_size: usize) -> StepDecay {
+        return StepDecay {
+            initial,
+            factor,
+            step_size,
+        };
+    }
+}
+
+impl Schedule for StepDecay {
+    fn learning_rate(&self, step: usize) -> f32 {
+        return self.initial * self.factor.powi((step / self.step_size.max(1)) as i32);
+    }
+}
@@ -0,0 +1,581 @@
+use crate::network::activation::{self, Activation};
+use crate::network::layer::Layer;
+use crate::network::matrix::{Element, Matrix};
+use crate::network::{AvgPool2D, Conv2D, Dense, Dropout, Flatten, GlobalAvgPool, LayerNorm, MaxPool2D, Padding};
+
+mod binary;
+mod json;
+
+pub const VERSION: u32 = 1;
+
+// Binary files store a layer's place in this list and json its name. A file of only dense layers
+// is the same as before the other types were added, so new types go on the end
+pub const LAYER_TYPES: [&str; 8] = ["dense", "conv2d", "max_pool2d", "avg_pool2d", "global_avg_pool", "flatten", "dropout", "layer_norm"];
+
+#[derive(Debug, PartialEq)]
+pub enum SerialisationError {
+    Io(std::io::ErrorKind),
+    UnrecognisedFormat,
+    UnsupportedVersion(u32),
+    Truncated,
+    InvalidJson,
+    UnknownLayerType(String),
+    UnknownActivation(String),
+    LayerShapesDoNotMatch,
+    // A network needs at least one layer
+    NoLayers,
+    // A field a layer can't be built with, like a stride of 0
+    InvalidSetting(String),
+}
+
+// Weights are always written as f32, so an f64 network only keeps its full precision in json.
+// Layers of a type from outside the crate can't be written
+pub fn to_binary<T: Element>(layers: &[Box<dyn Layer<T>>]) -> Result<Vec<u8>, SerialisationError> {
+    return binary::write(layers);
+}
+
+pub fn to_json<T: Element>(layers: &[Box<dyn Layer<T>>]) -> Result<String, SerialisationError> {
+    return json::write(layers);
+}
+
+// Works out the format from the start of the file, binary files begin with the magic bytes and json with a brace
+pub fn read<T: Element>(bytes: &[u8]) -> Result<Vec<Box<dyn Layer<T>>>, SerialisationError> {
+    let layers = if binary::is_binary(bytes) {
+        binary::read(bytes)?
+    } else if json::is_json(bytes) {
+        json::read(bytes)?
+    } else {
+        return Err(SerialisationError::UnrecognisedFormat);
+    };
+
+    validate(&layers)?;
+
+    return Ok(layers.into_iter().map(|l| l.layer).collect::<Vec<Box<dyn Layer<T>>>>());
+}
+
+// A layer as it was read, with how many inputs a sample of it needs when it cares, so it can be
+// checked against what the layer before gives
+struct ReadLayer<T: Element> {
+    layer: Box<dyn Layer<T>>,
+    inputs: Option<usize>,
+}
+
+fn validate<T: Element>(layers: &[ReadLayer<T>]) -> Result<(), SerialisationError> {
+    if layers.is_empty() {
+        return Err(SerialisationError::NoLayers);
+    }
+
+    let mut shape: Option<Vec<usize>> = None;
+
+    for ReadLayer { layer, inputs } in layers {
+        if let (Some(shape), Some(inputs)) = (&shape, inputs) {
+            if product(shape)? != *inputs {
+                return Err(SerialisationError::LayerShapesDoNotMatch);
+            }
+        }
+
+        shape = match (shape, inputs) {
+            (Some(shape), _) => Some(layer.output_shape(&shape)),
+            (None, Some(inputs)) => Some(layer.output_shape(&[*inputs])),
+            (None, None) => None,
+        };
+    }
+
+    return Ok(());
+}
+
+// How each format writes the fields of a layer. Binary files only keep the values, in the order
+// they were written, so a layer reads its fields back in the same order
+trait FieldWriter<T: Element> {
+    fn text(&mut self, name: &str, value: &str) -> ();
+
+    fn size(&mut self, name: &str, value: usize) -> ();
+
+    fn numbers(&mut self, name: &str, values: &[f32]) -> ();
+
+    // How many weights there are comes from the sizes before them, so it isn't written
+    fn weights(&mut self, name: &str, values: &[T]) -> ();
+}
+
+trait FieldReader<T: Element> {
+    fn text(&mut self, name: &str) -> Result<String, SerialisationError>;
+
+    fn size(&mut self, name: &str) -> Result<usize, SerialisationError>;
+
+    fn numbers(&mut self, name: &str) -> Result<Vec<f32>, SerialisationError>;
+
+    fn weights(&mut self, name: &str, count: usize) -> Result<Vec<T>, SerialisationError>;
+}
+
+// Where the layer's type is in LAYER_TYPES. write_layer makes sure it is the type it says it is
+fn layer_type<T: Element>(layer: &dyn Layer<T>) -> Result<usize, SerialisationError> {
+    return LAYER_TYPES.iter().position(|t| *t == layer.name()).ok_or_else(|| SerialisationError::UnknownLayerType(String::from(layer.name())));
+}
+
+fn write_layer<T: Element>(layer: &dyn Layer<T>, fields: &mut dyn FieldWriter<T>) -> Result<(), SerialisationError> {
+    let any = layer.as_any();
+
+    if let Some(l) = any.downcast_ref::<Dense<T>>() {
+        write_activation(l.activation.as_ref(), fields);
+        fields.size("cols", l.weights.cols());
+        fields.size("rows", l.weights.rows());
+        fields.weights("weights", l.weights.elements());
+
+        return Ok(());
+    }
+
+    if let Some(l) = any.downcast_ref::<Conv2D<T>>() {
+        let (zeros, same) = match l.padding() {
+            Padding::Zeros(zeros) => (zeros, false),
+            Padding::Same => (0, true),
+        };
+
+        write_activation(l.activation.as_ref(), fields);
+        write_input(l.input_shape(), fields);
+        fields.size("filters", l.filters());
+        write_pair("kernel", l.kernel(), fields);
+        write_pair("stride", l.stride(), fields);
+        fields.text("padding", if same { "same" } else { "zeros" });
+        fields.size("zeros", zeros);
+        write_pair("dilation", l.dilation(), fields);
+        fields.size("has_bias", l.bias.is_some() as usize);
+        fields.weights("weights", l.weights.elements());
+
+        if let Some(bias) = l.bias.as_ref() {
+            fields.weights("bias", bias.elements());
+        }
+
+        return Ok(());
+    }
+
+    if let Some(l) = any.downcast_ref::<MaxPool2D>() {
+        write_input(l.input_shape(), fields);
+        write_pair("window", l.window(), fields);
+        write_pair("stride", l.stride(), fields);
+
+        return Ok(());
+    }
+
+    if let Some(l) = any.downcast_ref::<AvgPool2D>() {
+        write_input(l.input_shape(), fields);
+        write_pair("window", l.window(), fields);
+        write_pair("stride", l.stride(), fields);
+
+        return Ok(());
+    }
+
+    if let Some(l) = any.downcast_ref::<GlobalAvgPool>() {
+        write_input(l.input_shape(), fields);
+
+        return Ok(());
+    }
+
+    if any.downcast_ref::<Flatten>().is_some() {
+        return Ok(());
+    }
+
+    if let Some(l) = any.downcast_ref::<Dropout>() {
+        fields.numbers("rate", &[l.rate()]);
+
+        return Ok(());
+    }
+
+    if let Some(l) = any.downcast_ref::<LayerNorm<T>>() {
+        fields.size("features", l.gamma.cols());
+        fields.weights("gamma", l.gamma.elements());
+        fields.weights("beta", l.beta.elements());
+
+        return Ok(());
+    }
+
+    return Err(SerialisationError::UnknownLayerType(String::from(layer.name())));
+}
+
+fn read_layer<T: Element>(layer_type: &str, fields: &mut dyn FieldReader<T>) -> Result<ReadLayer<T>, SerialisationError> {
+    return match layer_type {
+        "dense" => {
+            let activation = read_activation(fields)?;
+            let cols = fields.size("cols")?;
+            let rows = fields.size("rows")?;
+            let weights = Matrix::create(cols, rows, fields.weights("weights", product(&[cols, rows])?)?).map_err(|_| SerialisationError::LayerShapesDoNotMatch)?;
+
+            // Every layer needs at least the bias row
+            if rows == 0 {
+                return Err(SerialisationError::LayerShapesDoNotMatch);
+            }
+
+            Ok(ReadLayer { layer: Box::new(Dense { weights, activation }), inputs: Some(rows - 1) })
+        },
+        "conv2d" => {
+            let activation = read_activation(fields)?;
+            let input = read_input(fields)?;
+            let filters = fields.size("filters")?;
+            let kernel = read_pair("kernel", fields)?;
+            let stride = read_pair("stride", fields)?;
+            let padding = match (fields.text("padding")?.as_str(), fields.size("zeros")?) {
+                ("same", _) => Padding::Same,
+                ("zeros", zeros) => Padding::Zeros(zeros),
+                _ => return Err(SerialisationError::InvalidSetting(String::from("padding"))),
+            };
+            let dilation = read_pair("dilation", fields)?;
+            let has_bias = fields.size("has_bias")?;
+            let rows = product(&[input.0, kernel.0, kernel.1])?;
+            let weights = Matrix::create(filters, rows, fields.weights("weights", product(&[filters, rows])?)?).map_err(|_| SerialisationError::LayerShapesDoNotMatch)?;
+            let bias = match has_bias {
+                0 => None,
+                1 => Some(Matrix::create(filters, 1, fields.weights("bias", filters)?).map_err(|_| SerialisationError::LayerShapesDoNotMatch)?),
+                _ => return Err(SerialisationError::InvalidSetting(String::from("has_bias"))),
+            };
+            // Working out the output shape pads the input and spreads the kernel out, which
+            // can't overflow either. Both stay below the input and padding plus twice the kernel
+            let zeros = match padding {
+                Padding::Zeros(zeros) => zeros,
+                Padding::Same => 0,
+            };
+
+            for (size, kernel, dilation) in [(input.1, kernel.0, dilation.0), (input.2, kernel.1, dilation.1)] {
+                [size, product(&[zeros, 2])?, product(&[dilation, kernel, 2])?].iter()
+                    .try_fold(0_usize, |total, s| total.checked_add(*s))
+                    .ok_or(SerialisationError::LayerShapesDoNotMatch)?;
+            }
+
+            let layer = Conv2D::from_weights(input, kernel, activation, weights, bias).with_stride(stride).with_padding(padding).with_dilation(dilation);
+
+            Ok(ReadLayer { layer: Box::new(layer), inputs: Some(product(&[input.0, input.1, input.2])?) })
+        },
+        "max_pool2d" => {
+            let input = read_input(fields)?;
+            let layer = MaxPool2D::create(input, read_pair("window", fields)?).with_stride(read_pair("stride", fields)?);
+
+            Ok(ReadLayer { layer: Box::new(layer), inputs: Some(product(&[input.0, input.1, input.2])?) })
+        },
+        "avg_pool2d" => {
+            let input = read_input(fields)?;
+            let layer = AvgPool2D::create(input, read_pair("window", fields)?).with_stride(read_pair("stride", fields)?);
+
+            Ok(ReadLayer { layer: Box::new(layer), inputs: Some(product(&[input.0, input.1, input.2])?) })
+        },
+        "global_avg_pool" => {
+            let input = read_input(fields)?;
+
+            if input.1 == 0 || input.2 == 0 {
+                return Err(SerialisationError::InvalidSetting(String::from("input")));
+            }
+
+            Ok(ReadLayer { layer: Box::new(GlobalAvgPool::create(input)), inputs: Some(product(&[input.0, input.1, input.2])?) })
+        },
+        "flatten" => Ok(ReadLayer { layer: Box::new(Flatten {}), inputs: None }),
+        "dropout" => match fields.numbers("rate")?[..] {
+            [rate] if (0. ..1.).contains(&rate) => Ok(ReadLayer { layer: Box::new(Dropout::create(rate)), inputs: None }),
+            _ => Err(SerialisationError::InvalidSetting(String::from("rate"))),
+        },
+        "layer_norm" => {
+            let features = fields.size("features")?;
+            let gamma = Matrix::create(features, 1, fields.weights("gamma", features)?).map_err(|_| SerialisationError::LayerShapesDoNotMatch)?;
+            let beta = Matrix::create(features, 1, fields.weights("beta", features)?).map_err(|_| SerialisationError::LayerShapesDoNotMatch)?;
+
+            Ok(ReadLayer { layer: Box::new(LayerNorm { gamma, beta }), inputs: Some(features) })
+        },
+        _ => Err(SerialisationError::UnknownLayerType(String::from(layer_type))),
+    };
+}
+
+// Sizes come straight from the file, so multiplying them can overflow
+fn product(sizes: &[usize]) -> Result<usize, SerialisationError> {
+    return sizes.iter().try_fold(1_usize, |total, size| total.checked_mul(*size)).ok_or(SerialisationError::LayerShapesDoNotMatch);
+}
+
+fn write_activation<T: Element>(activation: &dyn Activation<T>, fields: &mut dyn FieldWriter<T>) -> () {
+    fields.text("activation", activation.name());
+    fields.numbers("parameters", &activation.parameters());
+}
+
+fn read_activation<T: Element>(fields: &mut dyn FieldReader<T>) -> Result<Box<dyn Activation<T>>, SerialisationError> {
+    let name = fields.text("activation")?;
+    let parameters = fields.numbers("parameters")?;
+
+    return activation::from_name(&name, &parameters).ok_or(SerialisationError::UnknownActivation(name));
+}
+
+fn write_input<T: Element>((channels, height, width): (usize, usize, usize), fields: &mut dyn FieldWriter<T>) -> () {
+    fields.size("channels", channels);
+    fields.size("height", height);
+    fields.size("width", width);
+}
+
+fn read_input<T: Element>(fields: &mut dyn FieldReader<T>) -> Result<(usize, usize, usize), SerialisationError> {
+    return Ok((fields.size("channels")?, fields.size("height")?, fields.size("width")?));
+}
+
+// A (height, width) setting of a layer, like the kernel or stride
+fn write_pair<T: Element>(name: &str, (height, width): (usize, usize), fields: &mut dyn FieldWriter<T>) -> () {
+    fields.size(&format!("{}_height", name), height);
+    fields.size(&format!("{}_width", name), width);
+}
+
+// None of the pairs can be 0, which the layers would panic on
+fn read_pair<T: Element>(name: &str, fields: &mut dyn FieldReader<T>) -> Result<(usize, usize), SerialisationError> {
+    let pair = (fields.size(&format!("{}_height", name))?, fields.size(&format!("{}_width", name))?);
+
+    if pair.0 == 0 || pair.1 == 0 {
+        return Err(SerialisationError::InvalidSetting(String::from(name)));
+    }
+
+    return Ok(pair);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use rand::{ChaChaRng, Rng, SeedableRng};
+    use crate::network::activation::{LeakyRelu, Relu, Sigmoid, Softmax, Tanh};
+    use crate::network::matrix::Matrix;
+    use crate::network::serialisation::{read, to_binary, to_json, SerialisationError};
+    use crate::network::tensor::Tensor;
+    use crate::network::{AvgPool2D, Conv2D, Dense, Dropout, Flatten, GlobalAvgPool, Gradients, Layer, LayerNorm, MaxPool2D, NetworkError, Padding, Saved};
+
+    fn layers() -> Vec<Box<dyn Layer>> {
+        return vec![
+            Box::new(Dense { weights: Matrix::create(3, 3, vec![0.5, -0.2, 0.1, 0.3, 0.8, -0.4, 0.1, 0.1, 0.0000001]).unwrap(), activation: Box::new(LeakyRelu::create(0.01)) }),
+            Box::new(Dense { weights: Matrix::create(2, 4, vec![0.4, -0.3, 0.2, 0.6, -0.5, 0.1, 1234.5678, -0.05]).unwrap(), activation: Box::new(Softmax {}) }),
+        ];
+    }
+
+    // Two channels of 6x5 all the way down to two outputs
+    fn mixed_layers() -> Vec<Box<dyn Layer>> {
+        let rng = &mut ChaChaRng::from_seed(&[3]);
+        let mut norm = LayerNorm::create(4);
+        norm.gamma = Matrix::create(4, 1, vec![0.5, 1.5, -1., 2.]).unwrap();
+        norm.beta = Matrix::create(4, 1, vec![0.1, 0.2, 0.3, 0.4]).unwrap();
+
+        return vec![
+            Box::new(Conv2D::create((2, 6, 5), 3, (3, 2), Box::new(Tanh {}), rng).with_stride((2, 1)).with_padding(Padding::Same).with_dilation((1, 2))),
+            Box::new(MaxPool2D::create((3, 3, 5), (2, 2)).with_stride((1, 1))),
+            Box::new(AvgPool2D::create((3, 2, 4), (1, 2))),
+            Box::new(Conv2D::create((3, 2, 2), 4, (1, 1), Box::new(LeakyRelu::create(0.1)), rng).with_padding(Padding::Zeros(1)).without_bias()),
+            Box::new(GlobalAvgPool::create((4, 4, 4))),
+            Box::new(Flatten {}),
+            Box::new(Dropout::create(0.25)),
+            Box::new(norm),
+            Box::new(Dense::create(2, 4, Box::new(Sigmoid {}), rng)),
+        ];
+    }
+
+    fn run(layers: &[Box<dyn Layer>]) -> Vec<f32> {
+        let inputs = Tensor::create(vec![2, 60], (0..120).map(|v| ((v * 7) % 13) as f32 / 6. - 1.).collect::<Vec<f32>>()).unwrap();
+
+        return layers.iter().fold(inputs, |food, layer| layer.forward(&food, None).unwrap().0).to_vec();
+    }
+
+    // A layer from outside the crate, which the formats know nothing about
+    struct Custom {}
+
+    impl Layer for Custom {
+        fn output_shape(&self, input: &[usize]) -> Vec<usize> {
+            return input.to_vec();
+        }
+
+        fn forward(&self, inputs: &Tensor, _rng: Option<&mut dyn Rng>) -> Result<(Tensor, Saved), NetworkError> {
+            return Ok((inputs.clone(), Saved::none()));
+        }
+
+        fn backward(&self, _inputs: &Tensor, _saved: &Saved, error: &Tensor) -> Result<Gradients, NetworkError> {
+            return Ok(Gradients { inputs: error.clone(), parameters: vec![] });
+        }
+
+        fn name(&self) -> &'static str {
+            return "custom";
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            return self;
+        }
+    }
+
+    fn assert_same_layers(result: Vec<Box<dyn Layer>>, expected: Vec<Box<dyn Layer>>) {
+        assert_eq!(result.len(), expected.len());
+
+        for (r, e) in result.iter().zip(expected.iter()) {
+            let r = r.as_any().downcast_ref::<Dense>().unwrap();
+            let e = e.as_any().downcast_ref::<Dense>().unwrap();
+
+            assert_eq!(r.weights, e.weights);
+            assert_eq!(r.activation.name(), e.activation.name());
+            assert_eq!(r.activation.parameters(), e.activation.parameters());
+        }
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let result = read(&to_binary(&layers()).unwrap()).expect("Could not read");
+
+        assert_same_layers(result, layers());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let result = read(to_json(&layers()).unwrap().as_bytes()).expect("Could not read");
+
+        assert_same_layers(result, layers());
+    }
+
+    #[test]
+    fn binary_truncated() {
+        let bytes = to_binary(&layers()).unwrap();
+
+        for length in [2, 6, 20, bytes.len() - 1] {
+            match read::<f32>(&bytes[0..length]) {
+                Ok(_) => panic!("Should error"),
+                Err(e) => assert_eq!(e, SerialisationError::Truncated),
+            };
+        }
+    }
+
+    #[test]
+    fn json_truncated() {
+        let json = to_json(&layers()).unwrap();
+
+        match read::<f32>(&json.as_bytes()[0..(json.len() / 2)]) {
+            Ok(_) => panic!("Should error"),
+            Err(e) => assert_eq!(e, SerialisationError::Truncated),
+        };
+    }
+
+    #[test]
+    fn unrecognised_format() {
+        match read::<f32>(b"not a network") {
+            Ok(_) => panic!("Should error"),
+            Err(e) => assert_eq!(e, SerialisationError::UnrecognisedFormat),
+        };
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut bytes = to_binary(&layers()).unwrap();
+        bytes[4] = 99;
+
+        match read::<f32>(&bytes) {
+            Ok(_) => panic!("Should error"),
+            Err(e) => assert_eq!(e, SerialisationError::UnsupportedVersion(99)),
+        };
+    }
+
+    #[test]
+    fn unknown_activation() {
+        let json = to_json(&layers()).unwrap().replace("\"softmax\"", "\"softmin\"");
+
+        match read::<f32>(json.as_bytes()) {
+            Ok(_) => panic!("Should error"),
+            Err(e) => assert_eq!(e, SerialisationError::UnknownActivation(String::from("softmin"))),
+        };
+    }
+
+    #[test]
+    fn layer_shapes_do_not_match() {
+        let mismatched: Vec<Box<dyn Layer>> = vec![
+            Box::new(Dense { weights: Matrix::create(2, 3, vec![0.; 6]).unwrap(), activation: Box::new(Relu {}) }),
+            Box::new(Dense { weights: Matrix::create(2, 4, vec![0.; 8]).unwrap(), activation: Box::new(Relu {}) }),
+        ];
+
+        match read::<f32>(&to_binary(&mismatched).unwrap()) {
+            Ok(_) => panic!("Should error"),
+            Err(e) => assert_eq!(e, SerialisationError::LayerShapesDoNotMatch),
+        };
+    }
+
+    #[test]
+    fn mixed_round_trip() {
+        let expected = run(&mixed_layers());
+
+        assert_eq!(run(&read(&to_binary(&mixed_layers()).unwrap()).expect("Could not read")), expected);
+        assert_eq!(run(&read(to_json(&mixed_layers()).unwrap().as_bytes()).expect("Could not read")), expected);
+
+        let layers = read::<f32>(to_json(&mixed_layers()).unwrap().as_bytes()).unwrap();
+        let conv = layers[0].as_any().downcast_ref::<Conv2D>().unwrap();
+        let pool = layers[1].as_any().downcast_ref::<MaxPool2D>().unwrap();
+
+        assert_eq!((conv.kernel(), conv.stride(), conv.padding(), conv.dilation()), ((3, 2), (2, 1), Padding::Same, (1, 2)));
+        assert!(layers[3].as_any().downcast_ref::<Conv2D>().unwrap().bias.is_none());
+        assert_eq!((pool.window(), pool.stride()), ((2, 2), (1, 1)));
+        assert_eq!(layers[6].as_any().downcast_ref::<Dropout>().unwrap().rate(), 0.25);
+        assert_eq!(layers.iter().map(|l| l.name()).collect::<Vec<&str>>(), mixed_layers().iter().map(|l| l.name()).collect::<Vec<&str>>());
+    }
+
+    #[test]
+    fn mixed_shapes_do_not_match() {
+        let rng = &mut ChaChaRng::from_seed(&[4]);
+        let mismatched: Vec<Box<dyn Layer>> = vec![
+            Box::new(Conv2D::create((1, 4, 4), 2, (3, 3), Box::new(Relu {}), rng)),
+            Box::new(Flatten {}),
+            Box::new(Dense::create(2, 7, Box::new(Relu {}), rng)),
+        ];
+
+        assert_eq!(read::<f32>(&to_binary(&mismatched).unwrap()).err(), Some(SerialisationError::LayerShapesDoNotMatch));
+    }
+
+    #[test]
+    fn invalid_settings() {
+        let pool: Vec<Box<dyn Layer>> = vec![Box::new(MaxPool2D::create((1, 4, 4), (2, 2)))];
+        let dropout: Vec<Box<dyn Layer>> = vec![Box::new(Dropout::create(0.5))];
+        let zero_stride = to_json(&pool).unwrap().replace("\"stride_width\": 2", "\"stride_width\": 0");
+        let every_input = to_json(&dropout).unwrap().replace("[0.5]", "[1]");
+
+        assert_eq!(read::<f32>(zero_stride.as_bytes()).err(), Some(SerialisationError::InvalidSetting(String::from("stride"))));
+        assert_eq!(read::<f32>(every_input.as_bytes()).err(), Some(SerialisationError::InvalidSetting(String::from("rate"))));
+    }
+
+    #[test]
+    fn unknown_layer_type() {
+        let custom: Vec<Box<dyn Layer>> = vec![Box::new(Flatten {}), Box::new(Custom {})];
+
+        assert_eq!(to_binary(&custom).err(), Some(SerialisationError::UnknownLayerType(String::from("custom"))));
+        assert_eq!(to_json(&custom).err(), Some(SerialisationError::UnknownLayerType(String::from("custom"))));
+        assert_eq!(read::<f32>(to_json(&layers()).unwrap().replace("\"dense\"", "\"dens\"").as_bytes()).err(), Some(SerialisationError::UnknownLayerType(String::from("dens"))));
+    }
+
+    #[test]
+    fn no_layers() {
+        let none: Vec<Box<dyn Layer>> = vec![];
+
+        assert_eq!(read::<f32>(&to_binary(&none).unwrap()).err(), Some(SerialisationError::NoLayers));
+        assert_eq!(read::<f32>(b"{\"version\": 1, \"layers\": []}").err(), Some(SerialisationError::NoLayers));
+    }
+
+    #[test]
+    fn non_finite_round_trip() {
+        let diverged: Vec<Box<dyn Layer>> = vec![
+            Box::new(Dense { weights: Matrix::create(2, 2, vec![f32::NAN, f32::INFINITY, f32::NEG_INFINITY, 0.5]).unwrap(), activation: Box::new(Relu {}) }),
+        ];
+
+        for layers in [read::<f32>(&to_binary(&diverged).unwrap()).unwrap(), read::<f32>(to_json(&diverged).unwrap().as_bytes()).unwrap()] {
+            let weights = layers[0].parameters()[0].elements().to_vec();
+
+            assert!(weights[0].is_nan());
+            assert_eq!(weights[1..], [f32::INFINITY, f32::NEG_INFINITY, 0.5]);
+        }
+    }
+
+    #[test]
+    fn sizes_overflow() {
+        let huge = to_json(&layers()).unwrap().replacen("\"cols\": 3", "\"cols\": 8589934592", 1).replacen("\"rows\": 3", "\"rows\": 8589934592", 1);
+
+        assert_eq!(read::<f32>(huge.as_bytes()).err(), Some(SerialisationError::LayerShapesDoNotMatch));
+
+        // Too many channels under too large a kernel in binary, where each size is a u32
+        let rng = &mut ChaChaRng::from_seed(&[5]);
+        let conv: Vec<Box<dyn Layer>> = vec![Box::new(Conv2D::create((1, 2, 2), 1, (1, 1), Box::new(Relu {}), rng).with_padding(Padding::Zeros(3)))];
+        let mut bytes = to_binary(&conv).unwrap();
+        // After the header, the type, the activation name "relu" and its parameter count
+        let channels = 12 + 1 + 8 + 4;
+
+        // The channels then the kernel height and width, after the height, width and filters
+        for i in [channels, channels + 16, channels + 20] {
+            bytes[i..(i + 4)].copy_from_slice(&u32::MAX.to_le_bytes());
+        }
+
+        assert_eq!(read::<f32>(&bytes).err(), Some(SerialisationError::LayerShapesDoNotMatch));
+
+        let padded = to_json(&conv).unwrap().replace("\"zeros\": 3", &format!("\"zeros\": {}", usize::MAX));
+        assert_eq!(read::<f32>(padded.as_bytes()).err(), Some(SerialisationError::LayerShapesDoNotMatch));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::network::layer::Layer;
+use crate::network::matrix::Element;
+use crate::network::serialisation::{self, FieldReader, FieldWriter, ReadLayer, SerialisationError, LAYER_TYPES, VERSION};
+
+// magic, version: u32, layer count: u32, then for each layer
+// layer type: u8, then the layer's fields in the order it writes them
+// Sizes are a u32, text is a u32 length then the bytes, numbers are a u32 count then f32s and
+// weights are f32s whose count comes from the sizes before them. A dense layer is
+// activation, parameters, cols, rows, weights
+// All numbers are little endian
+const MAGIC: &[u8; 4] = b"RCNN";
+
+pub fn is_binary(bytes: &[u8]) -> bool {
+    let length = bytes.len().min(MAGIC.len());
+
+    return length > 0 && bytes[0..length] == MAGIC[0..length];
+}
+
+pub fn write<T: Element>(layers: &[Box<dyn Layer<T>>]) -> Result<Vec<u8>, SerialisationError> {
+    let mut bytes = MAGIC.to_vec();
+
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(layers.len() as u32).to_le_bytes());
+
+    for layer in layers {
+        bytes.push(serialisation::layer_type(layer.as_ref())? as u8);
+        serialisation::write_layer(layer.as_ref(), &mut Writer { bytes: &mut bytes })?;
+    }
+
+    return Ok(bytes);
+}
+
+pub fn read<T: Element>(bytes: &[u8]) -> Result<Vec<ReadLayer<T>>, SerialisationError> {
+    let mut reader = Reader { bytes, position: 0 };
+
+    reader.take(MAGIC.len())?;
+
+    let version = reader.u32()?;
+
+    if version != VERSION {
+        return Err(SerialisationError::UnsupportedVersion(version));
+    }
+
+    let num_of_layers = reader.u32()?;
+    let mut layers = vec![];
+
+    for _ in 0..num_of_layers {
+        let layer_type = reader.u8()?;
+
+        match LAYER_TYPES.get(layer_type as usize) {
+            Some(name) => layers.push(serialisation::read_layer(name, &mut reader)?),
+            None => return Err(SerialisationError::UnknownLayerType(layer_type.to_string())),
+        }
+    }
+
+    return Ok(layers);
+}
+
+struct Writer<'a> {
+    bytes: &'a mut Vec<u8>,
+}
+
+impl<'a, T: Element> FieldWriter<T> for Writer<'a> {
+    fn text(&mut self, _name: &str, value: &str) -> () {
+        self.bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        self.bytes.extend_from_slice(value.as_bytes());
+    }
+
+    fn size(&mut self, _name: &str, value: usize) -> () {
+        self.bytes.extend_from_slice(&(value as u32).to_le_bytes());
+    }
+
+    fn numbers(&mut self, _name: &str, values: &[f32]) -> () {
+        self.bytes.extend_from_slice(&(values.len() as u32).to_le_bytes());
+
+        for v in values {
+            self.bytes.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+
+    fn weights(&mut self, _name: &str, values: &[T]) -> () {
+        for v in values {
+            self.bytes.extend_from_slice(&v.to_f32().to_le_bytes());
+        }
+    }
+}
+
+impl<'a, T: Element> FieldReader<T> for Reader<'a> {
+    fn text(&mut self, _name: &str) -> Result<String, SerialisationError> {
+        let length = self.u32()? as usize;
+
+        return Ok(String::from_utf8_lossy(self.take(length)?).into_owned());
+    }
+
+    fn size(&mut self, _name: &str) -> Result<usize, SerialisationError> {
+        return Ok(self.u32()? as usize);
+    }
+
+    fn numbers(&mut self, _name: &str) -> Result<Vec<f32>, SerialisationError> {
+        let count = self.u32()?;
+
+        return (0..count).map(|_| self.f32()).collect::<Result<Vec<f32>, SerialisationError>>();
+    }
+
+    fn weights(&mut self, _name: &str, count: usize) -> Result<Vec<T>, SerialisationError> {
+        return (0..count).map(|_| self.f32().map(T::from_f32)).collect::<Result<Vec<T>, SerialisationError>>();
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], SerialisationError> {
+        if self.bytes.len() - self.position < length {
+            return Err(SerialisationError::Truncated);
+        }
+
+        let result = &self.bytes[self.position..(self.position + length)];
+        self.position += length;
+
+        return Ok(result);
+    }
+
+    fn u8(&mut self) -> Result<u8, SerialisationError> {
+        return Ok(self.take(1)?[0]);
+    }
+
+    fn u32(&mut self) -> Result<u32, SerialisationError> {
+        let mut buffer = [0; 4];
+        buffer.copy_from_slice(self.take(4)?);
+
+        return Ok(u32::from_le_bytes(buffer));
+    }
+
+    fn f32(&mut self) -> Result<f32, SerialisationError> {
+        let mut buffer = [0; 4];
+        buffer.copy_from_slice(self.take(4)?);
+
+        return Ok(f32::from_le_bytes(buffer));
+    }
+}
+
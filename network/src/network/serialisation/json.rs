@@ -0,0 +1,336 @@
+use crate::network::layer::Layer;
+use crate::network::matrix::Element;
+use crate::network::serialisation::{self, FieldReader, FieldWriter, ReadLayer, SerialisationError, LAYER_TYPES, VERSION};
+
+pub fn is_json(bytes: &[u8]) -> bool {
+    return bytes.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{');
+}
+
+pub fn write<T: Element>(layers: &[Box<dyn Layer<T>>]) -> Result<String, SerialisationError> {
+    let mut out = format!("{{\n  \"version\": {},\n  \"layers\": [", VERSION);
+
+    for (i, layer) in layers.iter().enumerate() {
+        let mut writer = Writer { fields: vec![format!("\"type\": \"{}\"", LAYER_TYPES[serialisation::layer_type(layer.as_ref())?])] };
+        serialisation::write_layer(layer.as_ref(), &mut writer)?;
+
+        if i != 0 {
+            out += ",";
+        }
+
+        out += "\n    {\n";
+        out += &writer.fields.iter().map(|f| format!("      {}", f)).collect::<Vec<String>>().join(",\n");
+        out += "\n    }";
+    }
+
+    out += "\n  ]\n}\n";
+
+    return Ok(out);
+}
+
+// JSON has no NaN or infinity so they are written as the strings "NaN", "inf" and "-inf"
+fn join<T: Element>(values: &[T]) -> String {
+    return values.iter().map(|v| {
+        return match v.to_f64().is_finite() {
+            true => v.to_string(),
+            false => format!("\"{}\"", v.to_f64()),
+        };
+    }).collect::<Vec<String>>().join(", ");
+}
+
+pub fn read<T: Element>(bytes: &[u8]) -> Result<Vec<ReadLayer<T>>, SerialisationError> {
+    let mut parser = Parser { bytes, position: 0 };
+    let root = parser.value()?;
+    let version = root.field("version")?.number::<u32>()?;
+
+    if version != VERSION {
+        return Err(SerialisationError::UnsupportedVersion(version));
+    }
+
+    let mut layers = vec![];
+
+    for layer in root.field("layers")?.array()? {
+        let layer_type = layer.field("type")?.string()?;
+
+        layers.push(serialisation::read_layer(layer_type, &mut Reader { layer })?);
+    }
+
+    return Ok(layers);
+}
+
+// The fields of a layer, one to a line
+struct Writer {
+    fields: Vec<String>,
+}
+
+impl<T: Element> FieldWriter<T> for Writer {
+    fn text(&mut self, name: &str, value: &str) -> () {
+        self.fields.push(format!("\"{}\": \"{}\"", name, value));
+    }
+
+    fn size(&mut self, name: &str, value: usize) -> () {
+        self.fields.push(format!("\"{}\": {}", name, value));
+    }
+
+    fn numbers(&mut self, name: &str, values: &[f32]) -> () {
+        self.fields.push(format!("\"{}\": [{}]", name, join(values)));
+    }
+
+    fn weights(&mut self, name: &str, values: &[T]) -> () {
+        self.fields.push(format!("\"{}\": [{}]", name, join(values)));
+    }
+}
+
+struct Reader<'a> {
+    layer: &'a Json,
+}
+
+impl<'a, T: Element> FieldReader<T> for Reader<'a> {
+    fn text(&mut self, name: &str) -> Result<String, SerialisationError> {
+        return Ok(self.layer.field(name)?.string()?.to_owned());
+    }
+
+    fn size(&mut self, name: &str) -> Result<usize, SerialisationError> {
+        return self.layer.field(name)?.number::<usize>();
+    }
+
+    fn numbers(&mut self, name: &str) -> Result<Vec<f32>, SerialisationError> {
+        return self.layer.field(name)?.array()?.iter().map(|p| p.float::<f32>()).collect::<Result<Vec<f32>, SerialisationError>>();
+    }
+
+    fn weights(&mut self, name: &str, count: usize) -> Result<Vec<T>, SerialisationError> {
+        let weights = self.layer.field(name)?.array()?.iter().map(|w| w.float::<f64>().map(T::from_f64)).collect::<Result<Vec<T>, SerialisationError>>()?;
+
+        if weights.len() != count {
+            return Err(SerialisationError::LayerShapesDoNotMatch);
+        }
+
+        return Ok(weights);
+    }
+}
+
+// Numbers keep their text so floats are parsed straight to f32 without a lossy trip through f64.
+// Nothing in the format uses null, true or false so they are only parsed as literals
+enum Json {
+    Literal,
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn field(&self, name: &str) -> Result<&Json, SerialisationError> {
+        if let Json::Object(fields) = self {
+            if let Some((_, value)) = fields.iter().find(|(key, _)| key == name) {
+                return Ok(value);
+            }
+        }
+
+        return Err(SerialisationError::InvalidJson);
+    }
+
+    fn array(&self) -> Result<&Vec<Json>, SerialisationError> {
+        return match self {
+            Json::Array(values) => Ok(values),
+            _ => Err(SerialisationError::InvalidJson),
+        };
+    }
+
+    fn string(&self) -> Result<&str, SerialisationError> {
+        return match self {
+            Json::String(value) => Ok(value),
+            _ => Err(SerialisationError::InvalidJson),
+        };
+    }
+
+    fn number<T: std::str::FromStr>(&self) -> Result<T, SerialisationError> {
+        return match self {
+            Json::Number(text) => text.parse::<T>().map_err(|_| SerialisationError::InvalidJson),
+            _ => Err(SerialisationError::InvalidJson),
+        };
+    }
+
+    // A number, or one of the strings join writes for numbers JSON can't hold
+    fn float<T: std::str::FromStr>(&self) -> Result<T, SerialisationError> {
+        return match self {
+            Json::String(text) if ["NaN", "inf", "-inf"].contains(&text.as_str()) => text.parse::<T>().map_err(|_| SerialisationError::InvalidJson),
+            _ => self.number::<T>(),
+        };
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    // Running out of input part way through is reported as truncated rather than invalid
+    fn peek(&mut self) -> Result<u8, SerialisationError> {
+        while self.position < self.bytes.len() && self.bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+
+        return match self.bytes.get(self.position) {
+            Some(b) => Ok(*b),
+            None => Err(SerialisationError::Truncated),
+        };
+    }
+
+    fn next(&mut self) -> Result<u8, SerialisationError> {
+        let b = self.peek()?;
+        self.position += 1;
+
+        return Ok(b);
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), SerialisationError> {
+        return match self.next()? {
+            b if b == expected => Ok(()),
+            _ => Err(SerialisationError::InvalidJson),
+        };
+    }
+
+    fn literal(&mut self, text: &[u8]) -> Result<Json, SerialisationError> {
+        let end = self.position + text.len();
+
+        if end > self.bytes.len() {
+            return Err(SerialisationError::Truncated);
+        }
+
+        if &self.bytes[self.position..end] != text {
+            return Err(SerialisationError::InvalidJson);
+        }
+
+        self.position = end;
+
+        return Ok(Json::Literal);
+    }
+
+    fn value(&mut self) -> Result<Json, SerialisationError> {
+        return match self.peek()? {
+            b'{' => self.object(),
+            b'[' => self.array(),
+            b'"' => Ok(Json::String(self.string()?)),
+            b'n' => self.literal(b"null"),
+            b't' => self.literal(b"true"),
+            b'f' => self.literal(b"false"),
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => Err(SerialisationError::InvalidJson),
+        };
+    }
+
+    fn object(&mut self) -> Result<Json, SerialisationError> {
+        let mut fields = vec![];
+
+        self.expect(b'{')?;
+
+        if self.peek()? == b'}' {
+            self.position += 1;
+
+            return Ok(Json::Object(fields));
+        }
+
+        loop {
+            if self.peek()? != b'"' {
+                return Err(SerialisationError::InvalidJson);
+            }
+
+            let key = self.string()?;
+            self.expect(b':')?;
+            fields.push((key, self.value()?));
+
+            match self.next()? {
+                b',' => continue,
+                b'}' => return Ok(Json::Object(fields)),
+                _ => return Err(SerialisationError::InvalidJson),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, SerialisationError> {
+        let mut values = vec![];
+
+        self.expect(b'[')?;
+
+        if self.peek()? == b']' {
+            self.position += 1;
+
+            return Ok(Json::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+
+            match self.next()? {
+                b',' => continue,
+                b']' => return Ok(Json::Array(values)),
+                _ => return Err(SerialisationError::InvalidJson),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SerialisationError> {
+        let mut out = vec![];
+
+        self.expect(b'"')?;
+
+        loop {
+            let b = match self.bytes.get(self.position) {
+                Some(b) => *b,
+                None => return Err(SerialisationError::Truncated),
+            };
+            self.position += 1;
+
+            match b {
+                b'"' => return String::from_utf8(out).map_err(|_| SerialisationError::InvalidJson),
+                b'\\' => {
+                    let escaped = match self.bytes.get(self.position) {
+                        Some(e) => *e,
+                        None => return Err(SerialisationError::Truncated),
+                    };
+                    self.position += 1;
+
+                    match escaped {
+                        b'"' | b'\\' | b'/' => out.push(escaped),
+                        b'b' => out.push(0x08),
+                        b'f' => out.push(0x0c),
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'u' => {
+                            let end = self.position + 4;
+
+                            if end > self.bytes.len() {
+                                return Err(SerialisationError::Truncated);
+                            }
+
+                            let code = std::str::from_utf8(&self.bytes[self.position..end]).ok()
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(char::from_u32);
+
+                            match code {
+                                Some(c) => out.extend_from_slice(c.to_string().as_bytes()),
+                                None => return Err(SerialisationError::InvalidJson),
+                            }
+
+                            self.position = end;
+                        },
+                        _ => return Err(SerialisationError::InvalidJson),
+                    }
+                },
+                _ => out.push(b),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, SerialisationError> {
+        let start = self.position;
+
+        while self.position < self.bytes.len() && matches!(self.bytes[self.position], b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') {
+            self.position += 1;
+        }
+
+        return Ok(Json::Number(String::from_utf8_lossy(&self.bytes[start..self.position]).into_owned()));
+    }
+}
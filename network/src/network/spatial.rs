@@ -0,0 +1,40 @@
+use crate::network::matrix::Element;
+use crate::network::tensor::{Tensor, TensorError};
+
+// The batch size, once the inputs are known to be (batch, channels, height, width)
+pub fn check_inputs<T: Element>(operation: &'static str, inputs: &Tensor<T>, shape: (usize, usize, usize)) -> Result<usize, TensorError> {
+    let (channels, height, width) = shape;
+
+    if inputs.rank() != 4 {
+        return Err(TensorError::WrongRank {
+            operation,
+            expected: 4,
+            shape: inputs.shape().to_vec(),
+        });
+    }
+
+    if inputs.shape()[1..] != [channels, height, width] {
+        return Err(TensorError::ShapesDoNotMatch {
+            operation,
+            expected: vec![inputs.shape()[0], channels, height, width],
+            shape: inputs.shape().to_vec(),
+        });
+    }
+
+    return Ok(inputs.shape()[0]);
+}
+
+// Backward passes need the error of every output, so (batch, channels, height, width) exactly
+pub fn check_error<T: Element>(operation: &'static str, error: &Tensor<T>, batch: usize, shape: (usize, usize, usize)) -> Result<(), TensorError> {
+    let (channels, height, width) = shape;
+
+    if error.shape() != [batch, channels, height, width] {
+        return Err(TensorError::ShapesDoNotMatch {
+            operation,
+            expected: vec![batch, channels, height, width],
+            shape: error.shape().to_vec(),
+        });
+    }
+
+    return Ok(());
+}
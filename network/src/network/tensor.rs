@@ -0,0 +1,365 @@
+use std::ops::Range;
+use std::sync::Arc;
+use crate::network::matrix::{Element, Matrix};
+
+pub use self::error::TensorError;
+
+mod error;
+
+// An n-dimensional array, such as (batch, channels, height, width). The elements live in shared
+// storage that reshape, permute and slice point into instead of copying, with strides saying how
+// far apart neighbours along each axis are. Writing to storage that is shared copies it first,
+// so a view never changes the tensor it came from
+#[derive(Debug, Clone)]
+pub struct Tensor<T = f32> {
+    storage: Arc<Vec<T>>,
+    offset: usize,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+}
+
+impl<T: Element> Tensor<T> {
+    // The elements are in row-major order, the last axis changing fastest
+    pub fn create(shape: Vec<usize>, elements: Vec<T>) -> Result<Tensor<T>, TensorError> {
+        if elements.len() != shape.iter().product::<usize>() {
+            return Err(TensorError::WrongNumberOfElements {
+                operation: "create",
+                shape,
+                elements: elements.len(),
+            });
+        }
+
+        return Ok(Tensor::from_parts(shape, elements));
+    }
+
+    pub fn zeros(shape: Vec<usize>) -> Tensor<T> {
+        let len = shape.iter().product();
+
+        return Tensor::from_parts(shape, vec![T::zero(); len]);
+    }
+
+    // Gives a tensor of shape [rows, cols]
+    pub fn from_matrix(m: &Matrix<T>) -> Tensor<T> {
+        return Tensor::from_parts(vec![m.rows(), m.cols()], m.elements().to_vec());
+    }
+
+    pub fn to_matrix(&self) -> Result<Matrix<T>, TensorError> {
+        if self.rank() != 2 {
+            return Err(TensorError::WrongRank {
+                operation: "to_matrix",
+                expected: 2,
+                shape: self.shape.clone(),
+            });
+        }
+
+        return Ok(Matrix::create(self.shape[1], self.shape[0], self.to_vec())?);
+    }
+
+    // A row for each position along the first axis, such as each sample of a batch, holding
+    // everything under it
+    pub fn batch_matrix(&self) -> Result<Matrix<T>, TensorError> {
+        let batch = self.shape.first().copied().unwrap_or(1);
+
+        return Ok(Matrix::create(self.len() / batch.max(1), batch, self.to_vec())?);
+    }
+
+    fn from_parts(shape: Vec<usize>, elements: Vec<T>) -> Tensor<T> {
+        return Tensor {
+            storage: Arc::new(elements),
+            offset: 0,
+            strides: Tensor::<T>::row_major_strides(&shape),
+            shape,
+        };
+    }
+
+    fn row_major_strides(shape: &[usize]) -> Vec<usize> {
+        let mut strides = vec![1; shape.len()];
+
+        for axis in (1..shape.len()).rev() {
+            strides[axis - 1] = strides[axis] * shape[axis];
+        }
+
+        return strides;
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        return &self.shape;
+    }
+
+    pub fn strides(&self) -> &[usize] {
+        return &self.strides;
+    }
+
+    pub fn rank(&self) -> usize {
+        return self.shape.len();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.shape.iter().product();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    // Whether the elements sit one after another in row-major order
+    pub fn is_contiguous(&self) -> bool {
+        return self.strides == Tensor::<T>::row_major_strides(&self.shape);
+    }
+
+    pub fn get(&self, index: &[usize]) -> T {
+        return self.storage[self.offset_of(index)];
+    }
+
+    pub fn set(&mut self, index: &[usize], value: T) -> () {
+        let offset = self.offset_of(index);
+
+        Arc::make_mut(&mut self.storage)[offset] = value;
+    }
+
+    fn offset_of(&self, index: &[usize]) -> usize {
+        assert!(index.len() == self.rank() && index.iter().zip(self.shape.iter()).all(|(i, s)| i < s), "Index {:?} is outside a tensor of shape {:?}", index, self.shape);
+
+        return index.iter().zip(self.strides.iter()).fold(self.offset, |offset, (i, s)| offset + i * s);
+    }
+
+    // A view with a different shape but the same number of elements. Only a contiguous tensor
+    // can be reinterpreted without moving anything, so any other is copied first
+    pub fn reshape(&self, shape: Vec<usize>) -> Result<Tensor<T>, TensorError> {
+        if shape.iter().product::<usize>() != self.len() {
+            return Err(TensorError::WrongNumberOfElements {
+                operation: "reshape",
+                shape,
+                elements: self.len(),
+            });
+        }
+
+        let source = if self.is_contiguous() { self.clone() } else { self.contiguous() };
+
+        return Ok(Tensor {
+            storage: source.storage,
+            offset: source.offset,
+            strides: Tensor::<T>::row_major_strides(&shape),
+            shape,
+        });
+    }
+
+    // A view with the axes reordered, axis i of the result is axis axes[i] of self.
+    // [0, 2, 3, 1] turns (batch, channels, height, width) into (batch, height, width, channels)
+    pub fn permute(&self, axes: &[usize]) -> Result<Tensor<T>, TensorError> {
+        let mut seen = vec![false; self.rank()];
+
+        if axes.len() != self.rank() || axes.iter().any(|a| *a >= self.rank() || std::mem::replace(&mut seen[*a], true)) {
+            return Err(TensorError::InvalidPermutation {
+                rank: self.rank(),
+                axes: axes.to_vec(),
+            });
+        }
+
+        return Ok(Tensor {
+            storage: self.storage.clone(),
+            offset: self.offset,
+            shape: axes.iter().map(|a| self.shape[*a]).collect::<Vec<usize>>(),
+            strides: axes.iter().map(|a| self.strides[*a]).collect::<Vec<usize>>(),
+        });
+    }
+
+    // A view of the range of positions along one axis, keeping every other axis whole
+    pub fn slice(&self, axis: usize, range: Range<usize>) -> Result<Tensor<T>, TensorError> {
+        if axis >= self.rank() {
+            return Err(TensorError::InvalidAxis {
+                operation: "slice",
+                axis,
+                rank: self.rank(),
+            });
+        }
+
+        if range.start > range.end || range.end > self.shape[axis] {
+            return Err(TensorError::OutOfBounds {
+                operation: "slice",
+                axis,
+                index: range.start.max(range.end),
+                size: self.shape[axis],
+            });
+        }
+
+        let mut shape = self.shape.clone();
+        shape[axis] = range.end - range.start;
+
+        return Ok(Tensor {
+            storage: self.storage.clone(),
+            offset: self.offset + range.start * self.strides[axis],
+            shape,
+            strides: self.strides.clone(),
+        });
+    }
+
+    // A copy laid out in row-major order with storage of its own
+    pub fn contiguous(&self) -> Tensor<T> {
+        return Tensor::from_parts(self.shape.clone(), self.to_vec());
+    }
+
+    // The elements in row-major order of the shape
+    pub fn to_vec(&self) -> Vec<T> {
+        return self.offsets().into_iter().map(|o| self.storage[o]).collect::<Vec<T>>();
+    }
+
+    // Where each element is in storage, in row-major order. Steps through the index like an
+    // odometer, moving on by the axis' stride and winding back the axes that roll over
+    fn offsets(&self) -> Vec<usize> {
+        let len = self.len();
+
+        if self.is_contiguous() {
+            return (self.offset..(self.offset + len)).collect::<Vec<usize>>();
+        }
+
+        let mut offsets = Vec::with_capacity(len);
+
+        if len == 0 {
+            return offsets;
+        }
+
+        let mut index = vec![0; self.rank()];
+        let mut offset = self.offset;
+
+        loop {
+            offsets.push(offset);
+
+            let mut axis = self.rank();
+
+            loop {
+                if axis == 0 {
+                    return offsets;
+                }
+
+                axis -= 1;
+                index[axis] += 1;
+                offset += self.strides[axis];
+
+                if index[axis] < self.shape[axis] {
+                    break;
+                }
+
+                offset -= self.strides[axis] * self.shape[axis];
+                index[axis] = 0;
+            }
+        }
+    }
+}
+
+impl<T: Element> PartialEq for Tensor<T> {
+    fn eq(&self, other: &Self) -> bool {
+        return self.shape == other.shape && self.to_vec() == other.to_vec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::network::matrix::Matrix;
+    use crate::network::tensor::{Tensor, TensorError};
+
+    fn counting(shape: Vec<usize>) -> Tensor {
+        let len = shape.iter().product::<usize>();
+
+        return Tensor::create(shape, (0..len).map(|v| v as f32).collect::<Vec<f32>>()).unwrap();
+    }
+
+    #[test]
+    fn create() {
+        let t = counting(vec![2, 3, 4]);
+
+        assert_eq!(t.strides(), &[12, 4, 1]);
+        assert_eq!(t.get(&[1, 2, 3]), 23.);
+        assert_eq!(t.len(), 24);
+        assert!(t.is_contiguous());
+        assert_eq!(Tensor::create(vec![2, 2], vec![1., 2., 3.]), Err(TensorError::WrongNumberOfElements { operation: "create", shape: vec![2, 2], elements: 3 }));
+    }
+
+    #[test]
+    fn reshape_is_a_view() {
+        let t = counting(vec![2, 3, 4]);
+        let r = t.reshape(vec![6, 4]).unwrap();
+
+        assert!(Arc::ptr_eq(&t.storage, &r.storage));
+        assert_eq!(r.get(&[4, 1]), 17.);
+        assert_eq!(r.to_vec(), t.to_vec());
+        assert_eq!(t.reshape(vec![5, 5]), Err(TensorError::WrongNumberOfElements { operation: "reshape", shape: vec![5, 5], elements: 24 }));
+    }
+
+    #[test]
+    fn permute() {
+        let t = counting(vec![2, 3, 4]);
+        let p = t.permute(&[2, 0, 1]).unwrap();
+
+        assert_eq!(p.shape(), &[4, 2, 3]);
+        assert!(!p.is_contiguous());
+        assert!(Arc::ptr_eq(&t.storage, &p.storage));
+
+        for i in 0..2 {
+            for j in 0..3 {
+                for k in 0..4 {
+                    assert_eq!(p.get(&[k, i, j]), t.get(&[i, j, k]));
+                }
+            }
+        }
+
+        assert_eq!(p.to_vec()[0..6], [0., 4., 8., 12., 16., 20.]);
+        assert_eq!(t.permute(&[0, 0, 1]), Err(TensorError::InvalidPermutation { rank: 3, axes: vec![0, 0, 1] }));
+        assert_eq!(t.permute(&[1, 0]), Err(TensorError::InvalidPermutation { rank: 3, axes: vec![1, 0] }));
+    }
+
+    #[test]
+    fn slice() {
+        let t = counting(vec![2, 3, 4]);
+        let s = t.slice(1, 1..3).unwrap();
+
+        assert_eq!(s.shape(), &[2, 2, 4]);
+        assert!(Arc::ptr_eq(&t.storage, &s.storage));
+        assert_eq!(s.get(&[1, 0, 2]), t.get(&[1, 1, 2]));
+        assert_eq!(s.slice(2, 3..4).unwrap().to_vec(), vec![7., 11., 19., 23.]);
+        assert_eq!(t.slice(1, 2..4), Err(TensorError::OutOfBounds { operation: "slice", axis: 1, index: 4, size: 3 }));
+        assert_eq!(t.slice(3, 0..1), Err(TensorError::InvalidAxis { operation: "slice", axis: 3, rank: 3 }));
+    }
+
+    #[test]
+    fn reshape_copies_a_non_contiguous_view() {
+        let p = counting(vec![2, 3]).permute(&[1, 0]).unwrap();
+        let r = p.reshape(vec![6]).unwrap();
+
+        assert!(!Arc::ptr_eq(&p.storage, &r.storage));
+        assert_eq!(r.to_vec(), vec![0., 3., 1., 4., 2., 5.]);
+        assert_eq!(p.contiguous(), p);
+        assert!(p.contiguous().is_contiguous());
+    }
+
+    #[test]
+    fn set_copies_shared_storage() {
+        let t = counting(vec![2, 3]);
+        let mut s = t.slice(0, 1..2).unwrap();
+
+        s.set(&[0, 1], -1.);
+
+        assert_eq!(s.to_vec(), vec![3., -1., 5.]);
+        assert_eq!(t.get(&[1, 1]), 4.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_out_of_bounds_panics() {
+        counting(vec![2, 3]).get(&[0, 3]);
+    }
+
+    #[test]
+    fn matrix_conversions() {
+        let m = Matrix::create(3, 2, vec![1., 2., 3., 4., 5., 6.]).unwrap();
+        let t = Tensor::from_matrix(&m);
+
+        assert_eq!(t.shape(), &[2, 3]);
+        assert_eq!(t.get(&[1, 0]), m.get(0, 1));
+        assert_eq!(t.to_matrix(), Ok(m.clone()));
+        assert_eq!(t.permute(&[1, 0]).unwrap().to_matrix(), Ok(Matrix::transposition(&m)));
+        assert_eq!(counting(vec![1, 2, 3]).to_matrix(), Err(TensorError::WrongRank { operation: "to_matrix", expected: 2, shape: vec![1, 2, 3] }));
+        assert_eq!(counting(vec![2, 2, 3]).batch_matrix(), Ok(Matrix::create(6, 2, (0..12).map(|v| v as f32).collect::<Vec<f32>>()).unwrap()));
+    }
+}
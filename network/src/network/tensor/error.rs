@@ -0,0 +1,84 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::network::matrix::MatrixError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TensorError {
+    // The elements don't fill the shape, or a reshape would change how many there are
+    WrongNumberOfElements {
+        operation: &'static str,
+        shape: Vec<usize>,
+        elements: usize,
+    },
+    // The operation can't work with a tensor of this shape
+    ShapesDoNotMatch {
+        operation: &'static str,
+        expected: Vec<usize>,
+        shape: Vec<usize>,
+    },
+    // The operation needs a tensor with a different number of axes
+    WrongRank {
+        operation: &'static str,
+        expected: usize,
+        shape: Vec<usize>,
+    },
+    InvalidAxis {
+        operation: &'static str,
+        axis: usize,
+        rank: usize,
+    },
+    // permute needs every axis exactly once
+    InvalidPermutation {
+        rank: usize,
+        axes: Vec<usize>,
+    },
+    OutOfBounds {
+        operation: &'static str,
+        axis: usize,
+        index: usize,
+        size: usize,
+    },
+    // A matrix the tensor was lowered to for an operation
+    Matrix(MatrixError),
+}
+
+impl From<MatrixError> for TensorError {
+    fn from(e: MatrixError) -> TensorError {
+        return TensorError::Matrix(e);
+    }
+}
+
+impl Display for TensorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            TensorError::WrongNumberOfElements { operation, shape, elements } => {
+                write!(f, "{} needs {} elements for shape {:?} but has {}", operation, shape.iter().product::<usize>(), shape, elements)
+            },
+            TensorError::ShapesDoNotMatch { operation, expected, shape } => {
+                write!(f, "{} expected a tensor of shape {:?} but was given {:?}", operation, expected, shape)
+            },
+            TensorError::WrongRank { operation, expected, shape } => {
+                write!(f, "{} needs a tensor with {} axes but was given shape {:?}", operation, expected, shape)
+            },
+            TensorError::InvalidAxis { operation, axis, rank } => {
+                write!(f, "{} was given axis {} of a tensor with only {} axes", operation, axis, rank)
+            },
+            TensorError::InvalidPermutation { rank, axes } => {
+                write!(f, "{:?} is not a permutation of the {} axes", axes, rank)
+            },
+            TensorError::OutOfBounds { operation, axis, index, size } => {
+                write!(f, "{} used index {} on axis {} which only has {}", operation, index, axis, size)
+            },
+            TensorError::Matrix(e) => write!(f, "{}", e),
+        };
+    }
+}
+
+impl Error for TensorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            TensorError::Matrix(e) => Some(e),
+            _ => None,
+        };
+    }
+}
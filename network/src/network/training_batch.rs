@@ -1,4 +1,4 @@
-pub struct TrainingBatch {
-    pub input: Vec<f32>,
-    pub expected: Vec<f32>,
+pub struct TrainingBatch<T = f32> {
+    pub input: Vec<T>,
+    pub expected: Vec<T>,
 }
@@ -1,4 +1,4 @@
-use rand::random;
+use rand::Rng;
 use crate::function::Function;
 use super::position::Position;
 use super::label::Label;
@@ -8,9 +8,9 @@ pub struct DataSet {
 }
 
 impl DataSet {
-    pub fn generate(length: usize, classifier: &Function) -> DataSet {
+    pub fn generate(length: usize, classifier: &Function, rng: &mut dyn Rng) -> DataSet {
         let points = (0..length).map(|_| {
-            return DataPoint::random(classifier);
+            return DataPoint::random(classifier, rng);
         });
 
         return DataSet {
@@ -18,9 +18,9 @@ impl DataSet {
         };
     }
 
-    pub fn extend(&mut self, num: usize, classifier: &Function) -> () {
+    pub fn extend(&mut self, num: usize, classifier: &Function, rng: &mut dyn Rng) -> () {
         for _ in 0..num {
-            self.points.push(DataPoint::random(classifier));
+            self.points.push(DataPoint::random(classifier, rng));
         }
     }
 
@@ -36,8 +36,8 @@ pub struct DataPoint {
 }
 
 impl DataPoint {
-    pub fn random(classifier: &Function) -> DataPoint {
-        let position = DataPoint::random_position();
+    pub fn random(classifier: &Function, rng: &mut dyn Rng) -> DataPoint {
+        let position = DataPoint::random_position(rng);
 
         return DataPoint {
             position,
@@ -46,10 +46,10 @@ impl DataPoint {
         };
     }
 
-    fn random_position() -> Position {
+    fn random_position(rng: &mut dyn Rng) -> Position {
         return (
-            (random::<f32>() * 2.) - 1.,
-            (random::<f32>() * 2.) - 1.,
+            (rng.next_f32() * 2.) - 1.,
+            (rng.next_f32() * 2.) - 1.,
         );
     }
 }
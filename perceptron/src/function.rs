@@ -1,4 +1,4 @@
-use rand::random;
+use rand::Rng;
 use crate::label::Label;
 use crate::position::Position;
 
@@ -15,8 +15,8 @@ impl Function {
         };
     }
 
-    pub fn random() -> Function {
-        let seed = (random::<f32>() * 2.) - 1.;
+    pub fn random(rng: &mut dyn Rng) -> Function {
+        let seed = (rng.next_f32() * 2.) - 1.;
         let m;
 
         if seed.abs() > 0.85 {
@@ -25,7 +25,7 @@ impl Function {
             m = seed;
         }
 
-        let c = random::<f32>() - 0.5;
+        let c = rng.next_f32() - 0.5;
         return Self::create(m, c);
     }
 
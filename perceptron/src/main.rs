@@ -1,8 +1,12 @@
 use fps_counter::FPSCounter;
 use minifb::{Key, Menu, Window, WindowOptions};
+use rand::{ChaChaRng, Rng, SeedableRng};
 use raqote::{Color, DrawOptions, DrawTarget, PathBuilder, SolidSource, Source, StrokeStyle};
 
-use network::network::{Network, TrainingBatch};
+use network::network::{Axis, Matrix, Network, TrainingBatch};
+use network::network::activation::Softmax;
+use network::network::loss::CategoricalCrossEntropy;
+use network::network::schedule::{ReduceOnPlateau, Schedule};
 
 use self::color_palette::ColorPalette;
 use self::data::{DataPoint, DataSet};
@@ -25,7 +29,10 @@ fn main() {
         ..WindowOptions::default()
     }).unwrap();
 
-    let f = Function::random();
+    // Pass a seed as the first argument to replay a run
+    let seed = std::env::args().nth(1).and_then(|s| s.parse::<u32>().ok()).unwrap_or_else(|| rand::thread_rng().next_u32());
+    let mut rng = ChaChaRng::from_seed(&[seed]);
+    let f = Function::random(&mut rng);
 
     let mut menu = Menu::new("Train").unwrap();
     menu.add_item("Next", 0)
@@ -51,12 +58,15 @@ fn main() {
         ..StrokeStyle::default()
     };
 
-    let mut dataset = DataSet::generate(BATCH_SIZE, &f);
-    let mut network = Network::create(vec![2], 2);
+    let mut dataset = DataSet::generate(BATCH_SIZE, &f, &mut rng);
+    let mut network = Network::create(vec![(2, Box::new(Softmax {}))], 2, &mut rng)
+        .with_loss(Box::new(CategoricalCrossEntropy {}));
+    let mut schedule = ReduceOnPlateau::create(0.5, 0.5, 10);
     let mut generation = -1;
     let mut fps = FPSCounter::new();
     let mut correct;
     let mut accuracy = 0.;
+    let mut loss = 0.;
     let mut auto_play = false;
 
     while window.is_open() {
@@ -78,7 +88,7 @@ fn main() {
 
         if do_training {
             dataset.reset();
-            dataset.extend(BATCH_SIZE, &f);
+            dataset.extend(BATCH_SIZE, &f, &mut rng);
 
             generation += 1;
         }
@@ -97,17 +107,15 @@ fn main() {
                     return TrainingBatch{ input: vec![p.position.0, p.position.1], expected};
                 }).collect::<Vec<TrainingBatch>>();
 
-                network.train(batch, 0.5);
+                loss = network.train(batch, schedule.learning_rate(generation as usize)).unwrap();
+                schedule.record(loss);
             }
 
-            for p in dataset.points.iter_mut() {
-                let guess_value = network.feed_forward(vec![p.position.0, p.position.1]);
-                let mut max_index = 0;
-
-                if guess_value[0] < guess_value[1] {
-                    max_index = 1;
-                }
+            let guess_values = Matrix::from_rows(network.feed_forward_batch(dataset.points.iter().map(|p| {
+                return vec![p.position.0, p.position.1];
+            }).collect::<Vec<Vec<f32>>>()).unwrap()).unwrap();
 
+            for (p, max_index) in dataset.points.iter_mut().zip(guess_values.argmax(Axis::Cols)) {
                 let guess = match max_index {
                     0 => Label::A,
                     _ => Label::B,
@@ -135,7 +143,7 @@ fn main() {
             window.update();
         }
 
-        window.set_title(format!("y = {}x + {} | Accuracy: {}% | Generation: {} | Points: {} | FPS: {}", f.m, f.c, accuracy * 100., generation.to_string(), dataset.points.len(), fps.tick()).as_str());
+        window.set_title(format!("Seed: {} | y = {}x + {} | Accuracy: {}% | Loss: {:.4} | Generation: {} | Points: {} | FPS: {}", seed, f.m, f.c, accuracy * 100., loss, generation.to_string(), dataset.points.len(), fps.tick()).as_str());
     }
 }
 